@@ -0,0 +1,145 @@
+//! Benchmarks [`MacEatsClient::restaurants`] against a local fixture server
+//! that adds a fixed latency to every response.
+
+use std::{fmt::Write, net::SocketAddr, time::Duration};
+
+use chrono::Utc;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use maceats::{MacEatsClient, RetryPolicy, TIMEZONE};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    runtime::Runtime,
+};
+
+const LOCATIONS: usize = 16;
+const RESTAURANTS_PER_LOCATION: usize = 4;
+const LATENCY: Duration = Duration::from_millis(20);
+
+fn location_list_html() -> String {
+    let mut html = String::from("<html><body>");
+
+    for i in 0..LOCATIONS {
+        write!(
+            html,
+            r#"<div class="unit unit-location"><a href="/locations/location-{i}">Location {i}</a></div>"#
+        )
+        .unwrap();
+    }
+
+    html.push_str("</body></html>");
+    html
+}
+
+fn location_html(location: &str) -> String {
+    let mut html = String::from("<html><body>");
+
+    for i in 0..RESTAURANTS_PER_LOCATION {
+        write!(
+            html,
+            r#"<div class="unit">
+                <h1 class="title">Restaurant {i}</h1>
+                <h2 class="location">{location}</h2>
+                <div class="location-data">Level {i}</div>
+                <div class="location-phone">905-525-9140</div>
+                <div class="schedule"><table>"#
+        )
+        .unwrap();
+
+        let today = Utc::now().with_timezone(&TIMEZONE).date_naive();
+
+        for date in today.iter_days().take(7) {
+            write!(
+                html,
+                r#"<tr><td class="day">{}</td><td class="time">7:30 am - 9 pm</td></tr>"#,
+                date.format("%a %b %-d")
+            )
+            .unwrap();
+        }
+
+        html.push_str(
+            r#"</table></div><ul class="tags"><li>Coffee</li><li>Snacks</li></ul></div>"#,
+        );
+    }
+
+    html.push_str("</body></html>");
+    html
+}
+
+async fn respond(mut socket: TcpStream) {
+    let mut request = Vec::new();
+    let mut buf = [0; 1024];
+
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+        match socket.read(&mut buf).await {
+            Ok(0) | Err(_) => return,
+            Ok(n) => request.extend_from_slice(&buf[..n]),
+        }
+    }
+
+    let request = String::from_utf8_lossy(&request);
+    let path = request.split_whitespace().nth(1).unwrap_or("/");
+
+    // Any page other than the location list and location pages is not found,
+    // so the benchmark fails if `restaurants` starts fetching more pages.
+    let (status, body) = match path.strip_prefix("/locations/") {
+        Some(slug) => ("200 OK", location_html(&slug.replace('-', " "))),
+        None if path == "/locations" => ("200 OK", location_list_html()),
+        None => ("404 Not Found", String::new()),
+    };
+
+    tokio::time::sleep(LATENCY).await;
+
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+
+    let _ = socket.write_all(response.as_bytes()).await;
+}
+
+async fn serve() -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        while let Ok((socket, _)) = listener.accept().await {
+            tokio::spawn(respond(socket));
+        }
+    });
+
+    addr
+}
+
+fn restaurants_all(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let addr = runtime.block_on(serve());
+
+    let mut group = c.benchmark_group("restaurants_all");
+    group.sample_size(10);
+
+    for concurrency in [1, 4, 16] {
+        let client = MacEatsClient::builder()
+            .base_url(format!("http://{addr}/").parse().unwrap())
+            .retry(RetryPolicy::none())
+            .concurrency(concurrency)
+            .build()
+            .unwrap();
+
+        group.bench_with_input(
+            BenchmarkId::from_parameter(concurrency),
+            &client,
+            |b, client| {
+                b.to_async(&runtime).iter(|| async {
+                    let restaurants = client.restaurants().await.unwrap();
+                    assert_eq!(restaurants.len(), LOCATIONS * RESTAURANTS_PER_LOCATION);
+                });
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, restaurants_all);
+criterion_main!(benches);
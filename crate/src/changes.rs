@@ -0,0 +1,469 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Display, Formatter},
+};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::{CoffeeBrand, FoodType, Location, Restaurant, RestaurantId, Result, Snapshot, Times};
+
+/// The changes between two scrapes of MacEats.
+///
+/// Restaurants are matched by their [`RestaurantId`], and locations by their
+/// slug.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangeSet {
+    /// The locations only in the new scrape.
+    pub locations_added: Vec<Location>,
+
+    /// The locations only in the old scrape.
+    pub locations_removed: Vec<Location>,
+
+    /// The restaurants only in the new scrape.
+    pub restaurants_added: Vec<Restaurant>,
+
+    /// The restaurants only in the old scrape.
+    pub restaurants_removed: Vec<Restaurant>,
+
+    /// The restaurants in both scrapes that changed.
+    pub restaurants_changed: Vec<RestaurantChanges>,
+}
+
+impl ChangeSet {
+    /// Get the changes from the restaurants in `old` to the restaurants in
+    /// `new`.
+    ///
+    /// The locations compared are the locations of the restaurants.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if two restaurants in `old` or two
+    /// in `new` have the same [`RestaurantId`].
+    pub fn between(old: &[Restaurant], new: &[Restaurant]) -> Result<Self> {
+        let locations = |restaurants: &[Restaurant]| {
+            let mut seen = BTreeSet::new();
+
+            restaurants
+                .iter()
+                .map(|restaurant| &restaurant.location)
+                .filter(|location| seen.insert(&location.slug))
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+
+        Self::new(&locations(old), &locations(new), old, new)
+    }
+
+    /// Get the changes from the `old` [`Snapshot`] to the `new` one.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if two restaurants in either
+    /// snapshot have the same [`RestaurantId`].
+    pub fn between_snapshots(old: &Snapshot, new: &Snapshot) -> Result<Self> {
+        Self::new(
+            &old.locations,
+            &new.locations,
+            &old.restaurants,
+            &new.restaurants,
+        )
+    }
+
+    fn new(
+        old_locations: &[Location],
+        new_locations: &[Location],
+        old: &[Restaurant],
+        new: &[Restaurant],
+    ) -> Result<Self> {
+        RestaurantId::check_collisions(old)?;
+        RestaurantId::check_collisions(new)?;
+
+        let has_location = |locations: &[Location], location: &Location| {
+            locations.iter().any(|l| l.slug == location.slug)
+        };
+
+        let old_ids = old
+            .iter()
+            .map(|restaurant| (restaurant.id(), restaurant))
+            .collect::<BTreeMap<_, _>>();
+        let new_ids = new
+            .iter()
+            .map(|restaurant| (restaurant.id(), restaurant))
+            .collect::<BTreeMap<_, _>>();
+
+        Ok(Self {
+            locations_added: new_locations
+                .iter()
+                .filter(|location| !has_location(old_locations, location))
+                .cloned()
+                .collect(),
+            locations_removed: old_locations
+                .iter()
+                .filter(|location| !has_location(new_locations, location))
+                .cloned()
+                .collect(),
+            restaurants_added: new
+                .iter()
+                .filter(|restaurant| !old_ids.contains_key(&restaurant.id()))
+                .cloned()
+                .collect(),
+            restaurants_removed: old
+                .iter()
+                .filter(|restaurant| !new_ids.contains_key(&restaurant.id()))
+                .cloned()
+                .collect(),
+            restaurants_changed: new
+                .iter()
+                .filter_map(|restaurant| {
+                    old_ids
+                        .get(&restaurant.id())
+                        .map(|old| RestaurantChanges::new(old, restaurant))
+                })
+                .filter(|changes| !changes.is_empty())
+                .collect(),
+        })
+    }
+
+    /// Whether nothing changed.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.locations_added.is_empty()
+            && self.locations_removed.is_empty()
+            && self.restaurants_added.is_empty()
+            && self.restaurants_removed.is_empty()
+            && self.restaurants_changed.is_empty()
+    }
+}
+
+impl Display for ChangeSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for location in &self.locations_added {
+            writeln!(f, "+ location {location}")?;
+        }
+
+        for location in &self.locations_removed {
+            writeln!(f, "- location {location}")?;
+        }
+
+        for restaurant in &self.restaurants_added {
+            writeln!(f, "+ {restaurant} ({})", restaurant.location)?;
+        }
+
+        for restaurant in &self.restaurants_removed {
+            writeln!(f, "- {restaurant} ({})", restaurant.location)?;
+        }
+
+        self.restaurants_changed
+            .iter()
+            .try_for_each(|changes| write!(f, "{changes}"))
+    }
+}
+
+/// The changes to a [`Restaurant`] between two scrapes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RestaurantChanges {
+    /// The restaurant's id.
+    pub id: RestaurantId,
+
+    /// The restaurant's name.
+    pub name: String,
+
+    /// The change to the restaurant's location details, if any.
+    pub location_details: Option<Change<Option<String>>>,
+
+    /// The change to the restaurant's phone number, if any.
+    pub location_phone: Option<Change<Option<String>>>,
+
+    /// The food type tags the restaurant gained.
+    pub tags_added: BTreeSet<FoodType>,
+
+    /// The food type tags the restaurant lost.
+    pub tags_removed: BTreeSet<FoodType>,
+
+    /// The coffee brands the restaurant gained.
+    pub coffee_brands_added: BTreeSet<CoffeeBrand>,
+
+    /// The coffee brands the restaurant lost.
+    pub coffee_brands_removed: BTreeSet<CoffeeBrand>,
+
+    /// The changes to the restaurant's hours, on the dates in both scrapes'
+    /// schedules.
+    pub hours: BTreeMap<NaiveDate, Change<Times>>,
+}
+
+impl RestaurantChanges {
+    fn new(old: &Restaurant, new: &Restaurant) -> Self {
+        let hours = match (&old.schedule, &new.schedule) {
+            (Some(old), Some(new)) => old
+                .iter()
+                .filter_map(|(date, old)| {
+                    new.get(date)
+                        .filter(|new| new.normalized() != old.normalized())
+                        .map(|new| (*date, Change::new(old.clone(), new.clone())))
+                })
+                .collect(),
+            _ => BTreeMap::new(),
+        };
+
+        Self {
+            id: new.id(),
+            name: new.name.clone(),
+            location_details: Change::of(&old.location_details, &new.location_details),
+            location_phone: Change::of(&old.location_phone, &new.location_phone),
+            tags_added: new.tags.difference(&old.tags).cloned().collect(),
+            tags_removed: old.tags.difference(&new.tags).cloned().collect(),
+            coffee_brands_added: new
+                .coffee_brands
+                .difference(&old.coffee_brands)
+                .cloned()
+                .collect(),
+            coffee_brands_removed: old
+                .coffee_brands
+                .difference(&new.coffee_brands)
+                .cloned()
+                .collect(),
+            hours,
+        }
+    }
+
+    /// Whether nothing changed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.location_details.is_none()
+            && self.location_phone.is_none()
+            && self.tags_added.is_empty()
+            && self.tags_removed.is_empty()
+            && self.coffee_brands_added.is_empty()
+            && self.coffee_brands_removed.is_empty()
+            && self.hours.is_empty()
+    }
+}
+
+impl Display for RestaurantChanges {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let or_none = |value: &Option<String>| value.clone().unwrap_or_else(|| "none".to_owned());
+
+        writeln!(f, "~ {} ({})", self.name, self.id)?;
+
+        if let Some(Change { old, new }) = &self.location_details {
+            writeln!(f, "    details: {} → {}", or_none(old), or_none(new))?;
+        }
+
+        if let Some(Change { old, new }) = &self.location_phone {
+            writeln!(f, "    phone: {} → {}", or_none(old), or_none(new))?;
+        }
+
+        let tags = self
+            .tags_added
+            .iter()
+            .map(|tag| format!("+{tag}"))
+            .chain(self.tags_removed.iter().map(|tag| format!("-{tag}")))
+            .collect::<Vec<_>>();
+
+        if !tags.is_empty() {
+            writeln!(f, "    tags: {}", tags.join(" "))?;
+        }
+
+        let coffee_brands = self
+            .coffee_brands_added
+            .iter()
+            .map(|coffee_brand| format!("+{coffee_brand}"))
+            .chain(
+                self.coffee_brands_removed
+                    .iter()
+                    .map(|coffee_brand| format!("-{coffee_brand}")),
+            )
+            .collect::<Vec<_>>();
+
+        if !coffee_brands.is_empty() {
+            writeln!(f, "    coffee brands: {}", coffee_brands.join(" "))?;
+        }
+
+        self.hours
+            .iter()
+            .try_for_each(|(date, Change { old, new })| {
+                writeln!(f, "    {}: {old} → {new}", date.format("%a %b %-d"))
+            })
+    }
+}
+
+/// A value that changed between two scrapes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Change<T> {
+    /// The value in the old scrape.
+    pub old: T,
+
+    /// The value in the new scrape.
+    pub new: T,
+}
+
+impl<T> Change<T> {
+    const fn new(old: T, new: T) -> Self {
+        Self { old, new }
+    }
+}
+
+impl<T: Clone + PartialEq> Change<T> {
+    /// Get the change from `old` to `new`, if they differ.
+    fn of(old: &T, new: &T) -> Option<Self> {
+        (old != new).then(|| Self::new(old.clone(), new.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{date, location, schedule};
+
+    fn restaurant(name: &str, location: &Location) -> Restaurant {
+        Restaurant {
+            schedule: Some(schedule([(20, "7:30 am - 9 pm"), (21, "7:30 am - 9 pm")])),
+            tags: BTreeSet::from([FoodType::Coffee]),
+            ..crate::test_util::restaurant(name, location)
+        }
+    }
+
+    fn centre() -> Location {
+        location("Student Centre", "student-centre")
+    }
+
+    fn mills() -> Location {
+        location("Mills Library", "mills")
+    }
+
+    #[test]
+    fn finds_no_changes_between_identical_scrapes() {
+        let restaurants = [restaurant("Tim Hortons", &centre())];
+        let changes = ChangeSet::between(&restaurants, &restaurants).unwrap();
+
+        assert!(changes.is_empty());
+        assert_eq!(changes.to_string(), "");
+    }
+
+    #[test]
+    fn finds_added_and_removed_restaurants() {
+        let old = [
+            restaurant("Tim Hortons", &centre()),
+            restaurant("Starbucks", &centre()),
+        ];
+        let new = [
+            restaurant("Tim Hortons", &centre()),
+            restaurant("Williams", &mills()),
+        ];
+
+        let changes = ChangeSet::between(&old, &new).unwrap();
+
+        assert_eq!(changes.locations_added, [mills()]);
+        assert!(changes.locations_removed.is_empty());
+        assert_eq!(changes.restaurants_added, [new[1].clone()]);
+        assert_eq!(changes.restaurants_removed, [old[1].clone()]);
+        assert!(changes.restaurants_changed.is_empty());
+    }
+
+    #[test]
+    fn finds_changed_restaurants() {
+        let old = restaurant("Tim Hortons", &centre());
+        let mut new = old.clone();
+        new.location_details = Some("Main floor".to_owned());
+        new.tags = BTreeSet::from([FoodType::Snacks]);
+        new.coffee_brands = BTreeSet::from([CoffeeBrand::TimHortons]);
+        new.schedule
+            .as_mut()
+            .unwrap()
+            .insert(date(21), Times::Closed);
+
+        let changes = ChangeSet::between(&[old], &[new.clone()]).unwrap();
+
+        assert_eq!(
+            changes.restaurants_changed,
+            [RestaurantChanges {
+                id: new.id(),
+                name: "Tim Hortons".to_owned(),
+                location_details: Some(Change::new(None, Some("Main floor".to_owned()))),
+                location_phone: None,
+                tags_added: BTreeSet::from([FoodType::Snacks]),
+                tags_removed: BTreeSet::from([FoodType::Coffee]),
+                coffee_brands_added: BTreeSet::from([CoffeeBrand::TimHortons]),
+                coffee_brands_removed: BTreeSet::new(),
+                hours: BTreeMap::from([(
+                    date(21),
+                    Change::new("7:30 am - 9 pm".parse().unwrap(), Times::Closed)
+                )]),
+            }]
+        );
+    }
+
+    #[test]
+    fn ignores_hours_that_only_differ_in_order() {
+        let mut old = restaurant("Tim Hortons", &centre());
+        old.schedule
+            .as_mut()
+            .unwrap()
+            .insert(date(20), "7:30 am - 2 pm, 4 pm - 9 pm".parse().unwrap());
+
+        let mut new = old.clone();
+        new.schedule
+            .as_mut()
+            .unwrap()
+            .insert(date(20), "4 pm - 9 pm, 7:30 am - 2 pm".parse().unwrap());
+
+        assert!(ChangeSet::between(&[old], &[new]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn ignores_dates_in_only_one_scrape() {
+        let old = restaurant("Tim Hortons", &centre());
+        let mut new = old.clone();
+        let schedule = new.schedule.as_mut().unwrap();
+        schedule.remove(&date(20));
+        schedule.insert(date(22), Times::Closed);
+
+        assert!(ChangeSet::between(&[old], &[new]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn displays_changes() {
+        let old = [
+            restaurant("Tim Hortons", &centre()),
+            restaurant("Starbucks", &centre()),
+        ];
+
+        let mut changed = old[0].clone();
+        changed.location_phone = Some("905-525-9140".to_owned());
+        changed.tags.insert(FoodType::Snacks);
+        changed.coffee_brands.insert(CoffeeBrand::TimHortons);
+        changed
+            .schedule
+            .as_mut()
+            .unwrap()
+            .insert(date(20), "8 am - 5 pm".parse().unwrap());
+
+        let new = [changed, restaurant("Williams", &mills())];
+
+        assert_eq!(
+            ChangeSet::between(&old, &new).unwrap().to_string(),
+            "+ location Mills Library\n\
+             + Williams (Mills Library)\n\
+             - Starbucks (Student Centre)\n\
+             ~ Tim Hortons (student-centre:tim-hortons)\n    \
+             phone: none → 905-525-9140\n    \
+             tags: +Snacks\n    \
+             coffee brands: +Tim Hortons\n    \
+             Thu Oct 20: 7:30 am - 9:00 pm → 8:00 am - 5:00 pm\n"
+        );
+    }
+
+    #[test]
+    fn rejects_duplicate_ids() {
+        let restaurants = [
+            restaurant("Tim Hortons", &centre()),
+            restaurant("Tim Hortons", &centre()),
+        ];
+
+        assert!(matches!(
+            ChangeSet::between(&restaurants, &[]),
+            Err(crate::Error::RestaurantIdCollision { .. })
+        ));
+    }
+}
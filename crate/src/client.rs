@@ -0,0 +1,859 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use futures::{future, stream, Stream, StreamExt, TryStreamExt};
+use reqwest::{header::USER_AGENT, Client, StatusCode, Url};
+use scraper::Html;
+
+use crate::{
+    mirror::Mirror,
+    retry::{self, RetryPolicy},
+    CoffeeBrand, Error, FoodType, Location, LocationResolver, MirrorManifest,
+    OpenNowReconciliation, ParseReport, Restaurant, RestaurantId, RestaurantQuery, Result,
+    Snapshot, TIMEZONE,
+};
+
+/// The base url of MacEats.
+const DEFAULT_BASE_URL: &str = "https://maceats.mcmaster.ca/";
+
+/// The default maximum number of pages fetched at once.
+const DEFAULT_CONCURRENCY: usize = 4;
+
+/// Get the date on campus at `instant`.
+fn campus_date(instant: DateTime<Utc>) -> NaiveDate {
+    instant.with_timezone(&TIMEZONE).date_naive()
+}
+
+fn default_base_url() -> Url {
+    DEFAULT_BASE_URL
+        .parse()
+        .expect("static url should be valid")
+}
+
+/// A client for fetching data from MacEats.
+///
+/// A [`MacEatsClient`] can be pointed at a different base url (e.g. a local
+/// mirror or mock server), and can be given a pre-configured
+/// [`reqwest::Client`] to control connection pooling, proxies and the like.
+/// It can also read pages from a mirror saved by [`MacEatsClient::mirror_to`]
+/// instead of sending requests.
+#[derive(Debug, Clone)]
+pub struct MacEatsClient {
+    client: Client,
+    base_url: Url,
+    user_agent: Option<String>,
+    timeout: Option<Duration>,
+    retry: RetryPolicy,
+    concurrency: usize,
+    mirror: Option<Arc<Mirror>>,
+}
+
+impl MacEatsClient {
+    /// Create a new [`MacEatsClient`] pointed at MacEats with the default
+    /// settings.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            client: Client::new(),
+            base_url: default_base_url(),
+            user_agent: None,
+            timeout: None,
+            retry: RetryPolicy::default(),
+            concurrency: DEFAULT_CONCURRENCY,
+            mirror: None,
+        }
+    }
+
+    /// Create a new [`MacEatsClientBuilder`].
+    #[must_use]
+    pub fn builder() -> MacEatsClientBuilder {
+        MacEatsClientBuilder::new()
+    }
+
+    /// Get the base url of this [`MacEatsClient`].
+    #[must_use]
+    pub const fn base_url(&self) -> &Url {
+        &self.base_url
+    }
+
+    /// Get the [`RetryPolicy`] of this [`MacEatsClient`].
+    #[must_use]
+    pub const fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry
+    }
+
+    /// Get the maximum number of pages this [`MacEatsClient`] fetches at once.
+    #[must_use]
+    pub const fn concurrency(&self) -> usize {
+        self.concurrency
+    }
+
+    /// Get the mirror directory this [`MacEatsClient`] reads pages from, or
+    /// [`None`] if it sends requests.
+    #[must_use]
+    pub fn mirror_dir(&self) -> Option<&Path> {
+        self.mirror.as_deref().map(Mirror::dir)
+    }
+
+    /// Get the url of the location list.
+    #[must_use]
+    pub fn locations_url(&self) -> Url {
+        self.url("locations")
+    }
+
+    /// Get the url of the list of restaurants open now.
+    #[must_use]
+    pub fn open_now_url(&self) -> Url {
+        self.url("open-now")
+    }
+
+    /// Get the url of a [`Location`].
+    #[must_use]
+    pub fn location_url(&self, location: &Location) -> Url {
+        self.url(&format!("locations/{}", location.slug))
+    }
+
+    /// Get the url of a [`FoodType`], or [`None`] if MacEats has no page for
+    /// it.
+    #[must_use]
+    pub fn food_type_url(&self, food_type: &FoodType) -> Option<Url> {
+        food_type
+            .slug()
+            .map(|slug| self.url(&format!("types/{slug}")))
+    }
+
+    /// Get the url of the coffee brand list.
+    #[must_use]
+    pub fn coffee_brands_url(&self) -> Url {
+        self.url("types/coffee")
+    }
+
+    /// Get the url of a [`CoffeeBrand`].
+    #[must_use]
+    pub fn coffee_brand_url(&self, coffee_brand: &CoffeeBrand) -> Url {
+        self.url(&format!("types/coffee/{}", coffee_brand.slug()))
+    }
+
+    /// Get every [`Location`] on MacEats.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if sending the request or parsing the
+    /// response fails.
+    pub async fn locations(&self) -> Result<Vec<Location>> {
+        self.location_list(self.locations_url()).await
+    }
+
+    /// Get every [`CoffeeBrand`] currently listed on MacEats.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if sending the request or parsing the
+    /// response fails.
+    pub async fn coffee_brands(&self) -> Result<Vec<CoffeeBrand>> {
+        let url = self.coffee_brands_url();
+        let html = Html::parse_document(&self.fetch(url.clone()).await?);
+
+        CoffeeBrand::from_coffee_brand_list_html(&html).map_err(|e| e.with_url(&url))
+    }
+
+    /// Get the [`Location`] on MacEats with the slug `slug`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if sending the request or parsing the
+    /// response fails, or if no location has the slug `slug`.
+    pub async fn location(&self, slug: &str) -> Result<Location> {
+        self.locations()
+            .await?
+            .into_iter()
+            .find(|location| location.slug == slug)
+            .ok_or_else(|| Error::UnresolvedLocation(slug.to_owned()))
+    }
+
+    /// Get a [`LocationResolver`] for every [`Location`] on MacEats.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if sending the request or parsing the
+    /// response fails.
+    pub async fn location_resolver(&self) -> Result<LocationResolver> {
+        self.locations().await.map(LocationResolver::new)
+    }
+
+    /// Get the [`Restaurant`]s at a [`Location`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if sending the request or parsing the
+    /// response fails.
+    pub async fn location_restaurants(&self, location: &Location) -> Result<Vec<Restaurant>> {
+        let mut restaurants = self.restaurant_list(self.location_url(location)).await?;
+
+        for restaurant in &mut restaurants {
+            restaurant.location = location.clone();
+        }
+
+        Ok(restaurants)
+    }
+
+    /// Get the [`Restaurant`] on MacEats with the id `id`, if there is one.
+    ///
+    /// Only the page of the location in `id` is fetched.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if sending a request or parsing a
+    /// response fails, or if more than one restaurant has the id `id`.
+    pub async fn restaurant(&self, id: &RestaurantId) -> Result<Option<Restaurant>> {
+        let location = match self.location(id.location_slug()).await {
+            Ok(location) => location,
+            Err(Error::UnresolvedLocation(_)) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        let restaurants = self.location_restaurants(&location).await?;
+
+        RestaurantId::check_collisions(&restaurants)?;
+
+        Ok(restaurants
+            .into_iter()
+            .find(|restaurant| restaurant.id() == *id))
+    }
+
+    /// Get every [`Restaurant`] on MacEats.
+    ///
+    /// Up to [`concurrency`] location pages are fetched at once. The
+    /// [`Restaurant`]s are returned in the same order as the [`Location`]s they
+    /// are at, regardless of which page finishes first.
+    ///
+    /// [`concurrency`]: MacEatsClient::concurrency
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if sending the request or parsing the
+    /// response fails.
+    pub async fn restaurants(&self) -> Result<Vec<Restaurant>> {
+        let locations = self.locations().await?;
+
+        self.locations_restaurants(&locations).await
+    }
+
+    /// Get every [`Restaurant`] at each of `locations`, in the same order as
+    /// the [`Location`]s they are at.
+    pub(crate) async fn locations_restaurants(
+        &self,
+        locations: &[Location],
+    ) -> Result<Vec<Restaurant>> {
+        stream::iter(locations)
+            .map(|location| self.location_restaurants(location))
+            .buffered(self.concurrency)
+            .try_concat()
+            .await
+    }
+
+    /// Get a [`Stream`] of every [`Restaurant`] on MacEats.
+    ///
+    /// The [`Restaurant`]s at each [`Location`] are yielded as soon as that
+    /// location's page has been parsed, so locations whose pages finish first
+    /// are yielded first. The restaurants at any one location are kept in
+    /// order, but unlike [`MacEatsClient::restaurants`], the locations may not
+    /// be.
+    ///
+    /// # Errors
+    ///
+    /// The stream will yield an error if sending a request or parsing a
+    /// response fails.
+    pub fn restaurants_stream(&self) -> impl Stream<Item = Result<Restaurant>> + '_ {
+        stream::once(self.locations())
+            .map_ok(move |locations| {
+                stream::iter(locations)
+                    .map(move |location| async move {
+                        self.location_restaurants(&location)
+                            .await
+                            .map(|v| stream::iter(v).map(Ok))
+                    })
+                    .buffer_unordered(self.concurrency)
+                    .try_flatten()
+            })
+            .try_flatten()
+    }
+
+    /// Get every [`Restaurant`] on MacEats, with the [`CoffeeBrand`]s each
+    /// restaurant serves filled in from the page of each coffee brand.
+    ///
+    /// This fetches the coffee brand list and every coffee brand page on top of
+    /// the pages fetched by [`MacEatsClient::restaurants`]. Restaurants with
+    /// the same [`RestaurantId`] are given the same [`CoffeeBrand`]s.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if sending a request or parsing a
+    /// response fails, if the location of any restaurant on a coffee brand
+    /// page cannot be resolved, or if two restaurants on one coffee brand page
+    /// have the same [`RestaurantId`].
+    pub async fn restaurants_with_coffee_brands(&self) -> Result<Vec<Restaurant>> {
+        let (locations, coffee_brands) =
+            future::try_join(self.locations(), self.coffee_brands()).await?;
+        let resolver = LocationResolver::new(locations);
+
+        let (mut restaurants, membership) = future::try_join(
+            self.locations_restaurants(resolver.locations()),
+            self.coffee_brand_membership(&resolver, coffee_brands),
+        )
+        .await?;
+
+        for restaurant in &mut restaurants {
+            if let Some(coffee_brands) = membership.get(&restaurant.id()) {
+                restaurant
+                    .coffee_brands
+                    .extend(coffee_brands.iter().cloned());
+            }
+        }
+
+        Ok(restaurants)
+    }
+
+    /// Get all [`Restaurant`]s open now.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if sending the request or parsing the
+    /// response fails.
+    pub async fn open_now(&self) -> Result<Vec<Restaurant>> {
+        self.resolved_restaurant_list(self.open_now_url()).await
+    }
+
+    /// Fetch a [`Snapshot`] of MacEats.
+    ///
+    /// Every location page, coffee brand page and food type page is fetched
+    /// exactly once, along with the location list and coffee brand list.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if sending a request or parsing a
+    /// response fails.
+    pub async fn snapshot(&self) -> Result<Snapshot> {
+        Snapshot::fetch_with(self).await
+    }
+
+    /// Mirror MacEats into the directory `dir`, returning the
+    /// [`MirrorManifest`] that is also written to `dir/manifest.json`.
+    ///
+    /// The location list, every location page, the open now page, every food
+    /// type page, the coffee brand list and every coffee brand page are saved.
+    /// A [`MacEatsClient`] built with [`MacEatsClientBuilder::mirror`] reads
+    /// them back without sending any requests.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if sending a request, parsing a
+    /// response or writing to `dir` fails.
+    pub async fn mirror_to(&self, dir: impl AsRef<Path> + Send) -> Result<MirrorManifest> {
+        MirrorManifest::fetch_with(self, dir.as_ref()).await
+    }
+
+    /// Compare which of `restaurants` are open now according to their
+    /// schedules with which are listed on MacEats' open now page.
+    ///
+    /// This is useful for checking that [`Restaurant::open_at`] agrees with
+    /// MacEats when serving open restaurants from cached data. Restaurants that
+    /// open or close while the page is being fetched may be reported as
+    /// disagreeing.
+    ///
+    /// Schedules are checked at the instant the open now page was fetched,
+    /// which for a client reading from a mirror is when the page was saved.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if sending the request or parsing the
+    /// response fails.
+    pub async fn reconcile_open_now(
+        &self,
+        restaurants: &[Restaurant],
+    ) -> Result<OpenNowReconciliation> {
+        let (resolver, (mut listed, fetched_at)) = future::try_join(
+            self.location_resolver(),
+            self.dated_restaurant_list(self.open_now_url()),
+        )
+        .await?;
+
+        resolver.resolve_restaurants(&mut listed)?;
+
+        Ok(OpenNowReconciliation::new(
+            restaurants,
+            listed,
+            fetched_at.with_timezone(&TIMEZONE),
+        ))
+    }
+
+    /// Get the [`Restaurant`]s that match a [`RestaurantQuery`], sorted if
+    /// requested.
+    ///
+    /// Only the pages needed to answer the query are fetched: the pages of its
+    /// [`Location`]s if it has any, otherwise the pages of its
+    /// [`CoffeeBrand`]s or [`FoodType`]s if possible, otherwise every
+    /// location's page. The pages of its [`CoffeeBrand`]s are always fetched
+    /// to check which restaurants serve them.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if sending a request or parsing a
+    /// response fails.
+    pub async fn query(&self, query: &RestaurantQuery) -> Result<Vec<Restaurant>> {
+        query.fetch_with(self).await
+    }
+
+    /// Get the [`Restaurant`]s that serve a [`FoodType`].
+    ///
+    /// Note that this function will get, then filter all [`Restaurant`]s if
+    /// MacEats has no page for `food_type` (e.g. [`FoodType::Coffee`]).
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if sending the request or parsing the
+    /// response fails.
+    pub async fn food_type_restaurants(&self, food_type: &FoodType) -> Result<Vec<Restaurant>> {
+        if let Some(url) = self.food_type_url(food_type) {
+            self.resolved_restaurant_list(url).await
+        } else {
+            Ok(self
+                .restaurants()
+                .await?
+                .into_iter()
+                .filter(|r| r.tags.contains(food_type))
+                .collect())
+        }
+    }
+
+    /// Get a [`Stream`] of the [`Restaurant`]s that serve a [`FoodType`].
+    ///
+    /// If MacEats has no page for `food_type` (e.g. [`FoodType::Coffee`]),
+    /// this filters [`MacEatsClient::restaurants_stream`], yielding each
+    /// [`Restaurant`] as soon as its location's page has been parsed.
+    ///
+    /// # Errors
+    ///
+    /// The stream will yield an error if sending a request or parsing a
+    /// response fails.
+    pub fn food_type_restaurants_stream<'a>(
+        &'a self,
+        food_type: &'a FoodType,
+    ) -> impl Stream<Item = Result<Restaurant>> + 'a {
+        self.food_type_url(food_type).map_or_else(
+            || {
+                self.restaurants_stream()
+                    .try_filter(move |r| future::ready(r.tags.contains(food_type)))
+                    .left_stream()
+            },
+            |url| {
+                stream::once(self.resolved_restaurant_list(url))
+                    .map_ok(|v| stream::iter(v).map(Ok))
+                    .try_flatten()
+                    .right_stream()
+            },
+        )
+    }
+
+    /// Get the [`Restaurant`]s that serve a [`CoffeeBrand`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if sending the request or parsing the
+    /// response fails.
+    pub async fn coffee_brand_restaurants(
+        &self,
+        coffee_brand: &CoffeeBrand,
+    ) -> Result<Vec<Restaurant>> {
+        let mut restaurants = self
+            .resolved_restaurant_list(self.coffee_brand_url(coffee_brand))
+            .await?;
+
+        for restaurant in &mut restaurants {
+            restaurant.coffee_brands.insert(coffee_brand.clone());
+        }
+
+        Ok(restaurants)
+    }
+
+    /// Parse the restaurant list at `url` into a [`Vec<Restaurant>`].
+    ///
+    /// The [`Location`] of each restaurant is guessed from its name with
+    /// [`Location::new`]. Use [`MacEatsClient::resolved_restaurant_list`] to
+    /// get the locations as listed on MacEats.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if sending the request or parsing the
+    /// response fails.
+    pub async fn restaurant_list(&self, url: Url) -> Result<Vec<Restaurant>> {
+        self.dated_restaurant_list(url)
+            .await
+            .map(|(restaurants, _)| restaurants)
+    }
+
+    /// Parse the restaurant list at `url` into a [`Vec<Restaurant>`], along
+    /// with when the page was fetched.
+    ///
+    /// Schedules are dated relative to the day on campus the page was fetched.
+    async fn dated_restaurant_list(&self, url: Url) -> Result<(Vec<Restaurant>, DateTime<Utc>)> {
+        let (body, fetched_at) = self.fetch_page(url.clone()).await?;
+        let html = Html::parse_document(&body);
+
+        Restaurant::from_restaurant_list_html_as_of(&html, campus_date(fetched_at))
+            .map(|restaurants| (restaurants, fetched_at))
+            .map_err(|e| e.with_url(&url))
+    }
+
+    /// Parse the restaurant list at `url` into a [`Vec<Restaurant>`], resolving
+    /// the location of each restaurant with a [`LocationResolver`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if sending a request or parsing a
+    /// response fails, or if the location of any restaurant cannot be resolved.
+    pub async fn resolved_restaurant_list(&self, url: Url) -> Result<Vec<Restaurant>> {
+        let (resolver, mut restaurants) =
+            future::try_join(self.location_resolver(), self.restaurant_list(url)).await?;
+
+        resolver.resolve_restaurants(&mut restaurants)?;
+
+        Ok(restaurants)
+    }
+
+    /// Get the [`CoffeeBrand`]s each restaurant is listed under, by fetching the
+    /// page of each of `coffee_brands`.
+    pub(crate) async fn coffee_brand_membership(
+        &self,
+        resolver: &LocationResolver,
+        coffee_brands: Vec<CoffeeBrand>,
+    ) -> Result<BTreeMap<RestaurantId, BTreeSet<CoffeeBrand>>> {
+        self.listing_membership(
+            resolver,
+            coffee_brands
+                .into_iter()
+                .map(|coffee_brand| (self.coffee_brand_url(&coffee_brand), coffee_brand)),
+        )
+        .await
+    }
+
+    /// Fetch the restaurant list at each url, recording which restaurants are
+    /// listed under which value.
+    pub(crate) async fn listing_membership<T: Ord + Clone>(
+        &self,
+        resolver: &LocationResolver,
+        pages: impl IntoIterator<Item = (Url, T)>,
+    ) -> Result<BTreeMap<RestaurantId, BTreeSet<T>>> {
+        let lists = stream::iter(pages)
+            .map(|(url, value)| async move {
+                let mut restaurants = self.restaurant_list(url).await?;
+                resolver.resolve_restaurants(&mut restaurants)?;
+                RestaurantId::check_collisions(&restaurants)?;
+
+                Result::Ok((restaurants, value))
+            })
+            .buffered(self.concurrency)
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        let mut membership = BTreeMap::<_, BTreeSet<_>>::new();
+
+        for (restaurants, value) in lists {
+            for restaurant in restaurants {
+                membership
+                    .entry(restaurant.id())
+                    .or_default()
+                    .insert(value.clone());
+            }
+        }
+
+        Ok(membership)
+    }
+
+    /// Parse the location list at `url` into a [`Vec<Location>`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if sending the request or parsing the
+    /// response fails.
+    pub async fn location_list(&self, url: Url) -> Result<Vec<Location>> {
+        self.dated_location_list(url)
+            .await
+            .map(|(locations, _)| locations)
+    }
+
+    /// Parse the location list at `url` into a [`Vec<Location>`], along with
+    /// when the page was fetched.
+    pub(crate) async fn dated_location_list(
+        &self,
+        url: Url,
+    ) -> Result<(Vec<Location>, DateTime<Utc>)> {
+        let (body, fetched_at) = self.fetch_page(url.clone()).await?;
+        let html = Html::parse_document(&body);
+
+        Location::from_location_list_html(&html)
+            .map(|locations| (locations, fetched_at))
+            .map_err(|e| e.with_url(&url))
+    }
+
+    /// Leniently parse the restaurant list at `url` into a
+    /// [`ParseReport<Restaurant>`].
+    ///
+    /// See [`Restaurant::from_restaurant_list_html_lenient`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if sending the request fails.
+    pub async fn restaurant_list_lenient(&self, url: Url) -> Result<ParseReport<Restaurant>> {
+        let (body, fetched_at) = self.fetch_page(url.clone()).await?;
+        let html = Html::parse_document(&body);
+
+        Ok(
+            Restaurant::from_restaurant_list_html_lenient_as_of(&html, campus_date(fetched_at))
+                .with_url(&url),
+        )
+    }
+
+    /// Leniently parse the location list at `url` into a
+    /// [`ParseReport<Location>`].
+    ///
+    /// See [`Location::from_location_list_html_lenient`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if sending the request fails.
+    pub async fn location_list_lenient(&self, url: Url) -> Result<ParseReport<Location>> {
+        let html = Html::parse_document(&self.fetch(url.clone()).await?);
+
+        Ok(Location::from_location_list_html_lenient(&html).with_url(&url))
+    }
+
+    /// Fetch the body of the page at `url`.
+    ///
+    /// See [`MacEatsClient::fetch_page`].
+    async fn fetch(&self, url: Url) -> Result<String> {
+        self.fetch_page(url).await.map(|(body, _)| body)
+    }
+
+    /// Fetch the body of the page at `url`, along with when it was fetched,
+    /// retrying transient failures according to the [`RetryPolicy`].
+    ///
+    /// If this client reads from a mirror, the page is read from the mirror
+    /// instead, along with when it was saved.
+    pub(crate) async fn fetch_page(&self, url: Url) -> Result<(String, DateTime<Utc>)> {
+        if let Some(mirror) = &self.mirror {
+            return mirror.read(&self.base_url, &url).await;
+        }
+
+        let mut attempt = 1;
+
+        loop {
+            let (error, retry_after) = match self.fetch_once(url.clone()).await {
+                Ok(body) => return Ok((body, Utc::now())),
+                Err(failure) => failure,
+            };
+
+            if attempt >= self.retry.max_attempts()
+                || !retry::is_transient(&error, retry_after)
+                || retry_after.is_some_and(|delay| delay > self.retry.max_backoff())
+            {
+                return Err(error.into());
+            }
+
+            let delay = retry_after.unwrap_or_else(|| self.retry.delay(attempt));
+
+            log::debug!("attempt {attempt} to fetch {url} failed, retrying in {delay:?}: {error}");
+
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Make a single attempt at fetching the body of the page at `url`.
+    ///
+    /// On failure, the delay requested by the server (if any) is returned
+    /// alongside the error.
+    async fn fetch_once(
+        &self,
+        url: Url,
+    ) -> std::result::Result<String, (reqwest::Error, Option<Duration>)> {
+        let mut request = self.client.get(url);
+
+        if let Some(user_agent) = &self.user_agent {
+            request = request.header(USER_AGENT, user_agent);
+        }
+
+        if let Some(timeout) = self.timeout {
+            request = request.timeout(timeout);
+        }
+
+        let response = request.send().await.map_err(|e| (e, None))?;
+
+        let retry_after = (response.status() == StatusCode::TOO_MANY_REQUESTS)
+            .then(|| retry::retry_after(response.headers()))
+            .flatten();
+
+        response
+            .error_for_status()
+            .map_err(|e| (e, retry_after))?
+            .text()
+            .await
+            .map_err(|e| (e, None))
+    }
+
+    fn url(&self, path: &str) -> Url {
+        self.base_url.join(path).expect("path should be valid")
+    }
+}
+
+impl Default for MacEatsClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A builder for a [`MacEatsClient`].
+#[derive(Debug, Clone, Default)]
+pub struct MacEatsClientBuilder {
+    client: Option<Client>,
+    base_url: Option<Url>,
+    user_agent: Option<String>,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    retry: RetryPolicy,
+    concurrency: Option<usize>,
+    mirror: Option<PathBuf>,
+}
+
+impl MacEatsClientBuilder {
+    /// Create a new [`MacEatsClientBuilder`] with the default settings.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use a pre-configured [`reqwest::Client`] to send requests.
+    ///
+    /// Note that [`connect_timeout`] is ignored if a client is provided, as it
+    /// can only be set when a client is built.
+    ///
+    /// [`connect_timeout`]: MacEatsClientBuilder::connect_timeout
+    #[must_use]
+    pub fn client(mut self, client: Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Set the base url that all MacEats pages are fetched relative to.
+    ///
+    /// Defaults to `https://maceats.mcmaster.ca/`.
+    #[must_use]
+    pub fn base_url(mut self, base_url: Url) -> Self {
+        self.base_url = Some(base_url);
+        self
+    }
+
+    /// Set the `User-Agent` header sent with every request.
+    #[must_use]
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Set the timeout for every request, from sending the request until the
+    /// response body has been read.
+    #[must_use]
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set the timeout for connecting to the server.
+    #[must_use]
+    pub const fn connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = Some(connect_timeout);
+        self
+    }
+
+    /// Set the [`RetryPolicy`] used for every request.
+    ///
+    /// Defaults to [`RetryPolicy::default`], which makes up to 3 attempts.
+    #[must_use]
+    pub const fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Set the maximum number of pages fetched at once when a call needs more
+    /// than one page (e.g. [`MacEatsClient::restaurants`]).
+    ///
+    /// Defaults to 4. A value of `0` is treated as `1`.
+    #[must_use]
+    pub const fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = Some(concurrency);
+        self
+    }
+
+    /// Read pages from the mirror in the directory `dir` instead of sending
+    /// requests.
+    ///
+    /// The mirror is written by [`MacEatsClient::mirror_to`]. Pages are looked
+    /// up by their url relative to the base url, so the base url of the client
+    /// that wrote the mirror does not need to match. Reading a page missing
+    /// from the mirror returns [`Error::NotMirrored`].
+    ///
+    /// Schedules are dated relative to the day each page was saved, not the
+    /// day it is read, so reading the same mirror always gives the same
+    /// results.
+    #[must_use]
+    pub fn mirror(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.mirror = Some(dir.into());
+        self
+    }
+
+    /// Build the [`MacEatsClient`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the base url cannot be used as a
+    /// base, if building the [`reqwest::Client`] fails, or if the manifest of
+    /// the mirror cannot be read.
+    pub fn build(self) -> Result<MacEatsClient> {
+        let mut base_url = self.base_url.unwrap_or_else(default_base_url);
+
+        if base_url.cannot_be_a_base() {
+            return Err(Error::InvalidBaseUrl(base_url));
+        }
+
+        if !base_url.path().ends_with('/') {
+            base_url.set_path(&format!("{}/", base_url.path()));
+        }
+
+        let client = if let Some(client) = self.client {
+            client
+        } else {
+            let mut builder = Client::builder();
+
+            if let Some(connect_timeout) = self.connect_timeout {
+                builder = builder.connect_timeout(connect_timeout);
+            }
+
+            builder.build()?
+        };
+
+        Ok(MacEatsClient {
+            client,
+            base_url,
+            user_agent: self.user_agent,
+            timeout: self.timeout,
+            retry: self.retry,
+            concurrency: self.concurrency.unwrap_or(DEFAULT_CONCURRENCY).max(1),
+            mirror: self.mirror.map(Mirror::open).transpose()?.map(Arc::new),
+        })
+    }
+}
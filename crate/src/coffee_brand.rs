@@ -1,18 +1,32 @@
 use std::{
+    collections::BTreeSet,
     fmt::{self, Display, Formatter},
+    path::Path,
     str::FromStr,
 };
 
+use heck::ToKebabCase;
 use reqwest::Url;
+use scraper::{ElementRef, Html};
 use serde::{Deserialize, Serialize};
 
-use crate::{Error, Restaurant, Result};
+use crate::{selector, Error, Restaurant, Result, DEFAULT_CLIENT};
 
 /// A brand of coffee served at a [`Restaurant`].
 ///
+/// Brands that MacEats has added since this crate was released are parsed as
+/// [`CoffeeBrand::Unknown`], so use [`CoffeeBrand::is_known`] to tell them
+/// apart.
+///
+/// A known [`CoffeeBrand`] is serialized as its slug (e.g. `"tim-hortons"`),
+/// and a [`CoffeeBrand::Unknown`] as its name. Both forms, and the names of
+/// known brands, can be deserialized. The slug of a deserialized
+/// [`CoffeeBrand::Unknown`] is guessed from its name.
+///
 /// [`Restaurant`]: crate::Restaurant
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
-#[serde(rename_all = "kebab-case")]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+#[non_exhaustive]
 pub enum CoffeeBrand {
     /// Marley.
     Marley,
@@ -28,27 +42,99 @@ pub enum CoffeeBrand {
 
     /// Williams.
     Williams,
+
+    /// A brand this crate does not know about.
+    Unknown {
+        /// The brand's name, as shown on MacEats.
+        name: String,
+
+        /// The brand's MacEats slug.
+        slug: String,
+    },
 }
 
 impl CoffeeBrand {
-    /// Get the url for this [`CoffeeBrand`].
-    #[must_use]
-    pub fn url(&self) -> Url {
-        macro_rules! url {
-            ($slug:literal) => {
-                concat!("https://maceats.mcmaster.ca/types/coffee/", $slug)
-            };
-        }
+    /// Create a [`CoffeeBrand`] from its name and MacEats slug, as listed on
+    /// the coffee index page.
+    fn from_listing(name: &str, slug: &str) -> Self {
+        Self::all()
+            .iter()
+            .find(|brand| brand.slug() == slug || brand.to_string() == name)
+            .cloned()
+            .unwrap_or_else(|| Self::Unknown {
+                name: name.to_owned(),
+                slug: slug.to_owned(),
+            })
+    }
 
+    /// Get the MacEats slug for this [`CoffeeBrand`].
+    #[must_use]
+    pub fn slug(&self) -> &str {
         match self {
-            Self::Marley => url!("marley"),
-            Self::Rejuvenate => url!("rejuvenate"),
-            Self::Starbucks => url!("starbucks"),
-            Self::TimHortons => url!("tim-hortons"),
-            Self::Williams => url!("williams"),
+            Self::Marley => "marley",
+            Self::Rejuvenate => "rejuvenate",
+            Self::Starbucks => "starbucks",
+            Self::TimHortons => "tim-hortons",
+            Self::Williams => "williams",
+            Self::Unknown { slug, .. } => slug,
         }
-        .parse()
-        .expect("static url should be valid")
+    }
+
+    /// Whether this [`CoffeeBrand`] is one this crate knows about, rather than
+    /// a [`CoffeeBrand::Unknown`] discovered on MacEats.
+    #[must_use]
+    pub const fn is_known(&self) -> bool {
+        !matches!(self, Self::Unknown { .. })
+    }
+
+    /// Get every known [`CoffeeBrand`].
+    #[must_use]
+    pub const fn all() -> &'static [Self] {
+        &[
+            Self::Marley,
+            Self::Rejuvenate,
+            Self::Starbucks,
+            Self::TimHortons,
+            Self::Williams,
+        ]
+    }
+
+    /// Get every [`CoffeeBrand`] currently listed on MacEats.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if sending the request or parsing the
+    /// response fails.
+    pub async fn discover() -> Result<Vec<Self>> {
+        DEFAULT_CLIENT.coffee_brands().await
+    }
+
+    /// Parse a coffee brand list [`Html`] document into a
+    /// [`Vec<CoffeeBrand>`].
+    ///
+    /// Every link to a coffee brand page is included once, in the order it
+    /// first appears.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if parsing the response fails.
+    pub fn from_coffee_brand_list_html(html: &Html) -> Result<Vec<Self>> {
+        let mut seen = BTreeSet::new();
+
+        html.select(selector!(r#"a[href*="/types/coffee/"]"#))
+            .map(TryInto::try_into)
+            .filter(|brand: &Result<Self>| {
+                brand
+                    .as_ref()
+                    .map_or(true, |brand| seen.insert(brand.slug().to_owned()))
+            })
+            .collect()
+    }
+
+    /// Get the url for this [`CoffeeBrand`].
+    #[must_use]
+    pub fn url(&self) -> Url {
+        DEFAULT_CLIENT.coffee_brand_url(self)
     }
 
     /// Get the [`Restaurant`]s that serve this coffee brand.
@@ -60,7 +146,7 @@ impl CoffeeBrand {
     ///
     /// [`Restaurant`]: crate::Restaurant
     pub async fn restaurants(&self) -> Result<Vec<Restaurant>> {
-        Restaurant::from_restaurant_list_url(self.url()).await
+        DEFAULT_CLIENT.coffee_brand_restaurants(self).await
     }
 }
 
@@ -75,6 +161,7 @@ impl Display for CoffeeBrand {
                 Self::Starbucks => "Starbucks",
                 Self::TimHortons => "Tim Hortons",
                 Self::Williams => "Williams",
+                Self::Unknown { name, .. } => name,
             }
         )
     }
@@ -84,13 +171,107 @@ impl FromStr for CoffeeBrand {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self> {
-        match s {
-            "Marley" => Ok(Self::Marley),
-            "Rejuvenate" => Ok(Self::Rejuvenate),
-            "Starbucks" => Ok(Self::Starbucks),
-            "Tim Hortons" => Ok(Self::TimHortons),
-            "Williams" => Ok(Self::Williams),
-            s => Err(Error::ParseCoffeeBrand(s.into())),
+        match s.trim() {
+            "Marley" | "marley" => Ok(Self::Marley),
+            "Rejuvenate" | "rejuvenate" => Ok(Self::Rejuvenate),
+            "Starbucks" | "starbucks" => Ok(Self::Starbucks),
+            "Tim Hortons" | "tim-hortons" => Ok(Self::TimHortons),
+            "Williams" | "williams" => Ok(Self::Williams),
+            "" => Err(Error::ParseCoffeeBrand(s.into())),
+            name => Ok(Self::Unknown {
+                name: name.to_owned(),
+                slug: name.to_kebab_case(),
+            }),
+        }
+    }
+}
+
+impl TryFrom<String> for CoffeeBrand {
+    type Error = Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<CoffeeBrand> for String {
+    fn from(coffee_brand: CoffeeBrand) -> Self {
+        match coffee_brand {
+            CoffeeBrand::Unknown { name, .. } => name,
+            coffee_brand => coffee_brand.slug().to_owned(),
+        }
+    }
+}
+
+impl TryFrom<ElementRef<'_>> for CoffeeBrand {
+    type Error = Error;
+
+    fn try_from(element: ElementRef<'_>) -> Result<Self> {
+        debug_assert_eq!(element.value().name(), "a");
+
+        let name = element
+            .text()
+            .next()
+            .ok_or_else(|| Error::text_not_found("coffee brand", "a").with_element(element))?
+            .trim();
+
+        let slug_not_found = || {
+            Error::attribute_not_found("coffee brand href", "a")
+                .with_subject(name)
+                .with_element(element)
+        };
+
+        let slug = Path::new(element.value().attr("href").ok_or_else(slug_not_found)?)
+            .file_name()
+            .ok_or_else(slug_not_found)?
+            .to_str()
+            .ok_or_else(slug_not_found)?;
+
+        Ok(Self::from_listing(name, slug))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unknown(name: &str, slug: &str) -> CoffeeBrand {
+        CoffeeBrand::Unknown {
+            name: name.to_owned(),
+            slug: slug.to_owned(),
         }
     }
+
+    #[test]
+    fn parses_trimmed_names() {
+        assert_eq!(
+            " Starbucks ".parse::<CoffeeBrand>().unwrap(),
+            CoffeeBrand::Starbucks
+        );
+        assert_eq!(
+            "tim-hortons".parse::<CoffeeBrand>().unwrap(),
+            CoffeeBrand::TimHortons
+        );
+        assert_eq!(
+            " Balzac's ".parse::<CoffeeBrand>().unwrap(),
+            unknown("Balzac's", "balzac-s")
+        );
+        assert!(" ".parse::<CoffeeBrand>().is_err());
+    }
+
+    #[test]
+    fn serializes_known_brands_as_slugs() {
+        let brands = vec![CoffeeBrand::TimHortons, unknown("Balzac's", "balzac-s")];
+        let json = serde_json::to_string(&brands).unwrap();
+
+        assert_eq!(json, r#"["tim-hortons","Balzac's"]"#);
+        assert_eq!(
+            serde_json::from_str::<Vec<CoffeeBrand>>(&json).unwrap(),
+            brands
+        );
+        assert_eq!(
+            serde_json::from_str::<CoffeeBrand>(r#""Tim Hortons""#).unwrap(),
+            CoffeeBrand::TimHortons
+        );
+    }
 }
@@ -1,8 +1,19 @@
+use std::fmt::{self, Display, Formatter};
+
+use chrono::NaiveDate;
+use scraper::ElementRef;
 use thiserror::Error;
+use url::Url;
+
+use crate::RestaurantId;
+
+/// The maximum number of characters of outer HTML kept in an [`ErrorContext`].
+const HTML_EXCERPT_LEN: usize = 200;
 
 /// An error type representing all possible errors that can occur when using
 /// this crate.
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum Error {
     /// A [`reqwest::Error`] occurred.
     #[error("reqwest error")]
@@ -12,25 +23,96 @@ pub enum Error {
     #[error("url parse error")]
     ParseUrl(#[from] url::ParseError),
 
+    /// A [`serde_json::Error`] occurred.
+    #[error("json error")]
+    Json(#[from] serde_json::Error),
+
+    /// A [`bincode::Error`] occurred.
+    #[error("bincode error")]
+    Bincode(#[from] bincode::Error),
+
+    /// A [`std::io::Error`] occurred.
+    #[error("io error")]
+    Io(#[from] std::io::Error),
+
+    /// A base url was given that cannot be used as a base.
+    #[error("invalid base url error: {0}")]
+    InvalidBaseUrl(url::Url),
+
     /// A [`chrono::ParseError`] occurred.
     #[error("chrono parse error")]
     ParseChrono(#[from] chrono::ParseError),
 
     /// No element matching the selector was found.
-    #[error("no element matching selector error: {0}")]
-    ElementNotFound(&'static str),
+    #[error("no element matching selector error: {what}{context}")]
+    ElementNotFound {
+        /// What the element describes.
+        what: &'static str,
+
+        /// Where the error occurred.
+        context: Box<ErrorContext>,
+    },
 
     /// No text was found in the element.
-    #[error("element text not found error: {0}")]
-    TextNotFound(&'static str),
+    #[error("element text not found error: {what}{context}")]
+    TextNotFound {
+        /// What the element describes.
+        what: &'static str,
+
+        /// Where the error occurred.
+        context: Box<ErrorContext>,
+    },
 
     /// No attribute was found in the element matching the selector.
-    #[error("no attribute matching selector error: {0}")]
-    AttributeNotFound(&'static str),
+    #[error("no attribute matching selector error: {what}{context}")]
+    AttributeNotFound {
+        /// What the attribute describes.
+        what: &'static str,
+
+        /// Where the error occurred.
+        context: Box<ErrorContext>,
+    },
+
+    /// An error ocurred while splitting a time range into its start and end.
+    #[error("error splitting time range{context}")]
+    SplitTime {
+        /// Where the error occurred.
+        context: Box<ErrorContext>,
+    },
+
+    /// A time of day could not be parsed.
+    #[error("time parse error: {time:?}{context}")]
+    ParseTime {
+        /// The time, as shown on MacEats.
+        time: String,
+
+        /// Where the error occurred.
+        context: Box<ErrorContext>,
+    },
+
+    /// A schedule row's day/date label could not be parsed.
+    #[error("schedule label parse error: {label:?}{context}")]
+    ParseScheduleLabel {
+        /// The label, as shown on MacEats.
+        label: String,
 
-    /// An error ocurred while splitting time on ` - `.
-    #[error("error splitting time on ` - `")]
-    SplitTime,
+        /// Where the error occurred.
+        context: Box<ErrorContext>,
+    },
+
+    /// A schedule row's day/date label does not match the date expected from
+    /// its position in the schedule.
+    #[error("schedule label mismatch error: {label:?} is not {expected}{context}")]
+    ScheduleMismatch {
+        /// The label, as shown on MacEats.
+        label: String,
+
+        /// The date expected from the row's position in the schedule.
+        expected: NaiveDate,
+
+        /// Where the error occurred.
+        context: Box<ErrorContext>,
+    },
 
     /// An error occurred while parsing a [`FoodType`].
     ///
@@ -44,9 +126,232 @@ pub enum Error {
     #[error("coffee brand parse error: {0}")]
     ParseCoffeeBrand(String),
 
+    /// No [`Location`] listed on MacEats matches a location name or slug.
+    ///
+    /// [`Location`]: crate::Location
+    #[error("unresolved location error: {0}")]
+    UnresolvedLocation(String),
+
+    /// An error occurred while parsing a [`RestaurantId`].
+    ///
+    /// [`RestaurantId`]: crate::RestaurantId
+    #[error("restaurant id parse error: {0}")]
+    ParseRestaurantId(String),
+
+    /// Two restaurants have the same [`RestaurantId`], whether or not their
+    /// names are exactly the same.
+    ///
+    /// [`RestaurantId`]: crate::RestaurantId
+    #[error("restaurant id collision error: {id} is both {:?} and {:?}", names.0, names.1)]
+    RestaurantIdCollision {
+        /// The id both restaurants have.
+        id: RestaurantId,
+
+        /// The names of the restaurants.
+        names: (String, String),
+    },
+
+    /// A page was requested from a mirror that does not contain it.
+    #[error("page not mirrored error: {0}")]
+    NotMirrored(url::Url),
+
     /// A miscellaneous error occurred.
-    #[error("miscellaneous error: {0}")]
-    Misc(&'static str),
+    #[error("miscellaneous error: {message}{context}")]
+    Misc {
+        /// A description of the error.
+        message: &'static str,
+
+        /// Where the error occurred.
+        context: Box<ErrorContext>,
+    },
+}
+
+impl Error {
+    /// Create an [`Error::ElementNotFound`] for an element matching `selector`.
+    pub(crate) fn element_not_found(what: &'static str, selector: &'static str) -> Self {
+        Self::ElementNotFound {
+            what,
+            context: Box::new(ErrorContext::selector(selector)),
+        }
+    }
+
+    /// Create an [`Error::TextNotFound`] for an element matching `selector`.
+    pub(crate) fn text_not_found(what: &'static str, selector: &'static str) -> Self {
+        Self::TextNotFound {
+            what,
+            context: Box::new(ErrorContext::selector(selector)),
+        }
+    }
+
+    /// Create an [`Error::AttributeNotFound`] for an element matching
+    /// `selector`.
+    pub(crate) fn attribute_not_found(what: &'static str, selector: &'static str) -> Self {
+        Self::AttributeNotFound {
+            what,
+            context: Box::new(ErrorContext::selector(selector)),
+        }
+    }
+
+    /// Create an [`Error::SplitTime`].
+    pub(crate) fn split_time() -> Self {
+        Self::SplitTime {
+            context: Box::default(),
+        }
+    }
+
+    /// Create an [`Error::ParseTime`].
+    pub(crate) fn parse_time(time: &str) -> Self {
+        Self::ParseTime {
+            time: time.to_owned(),
+            context: Box::default(),
+        }
+    }
+
+    /// Create an [`Error::ParseScheduleLabel`].
+    pub(crate) fn schedule_label(label: &str) -> Self {
+        Self::ParseScheduleLabel {
+            label: label.to_owned(),
+            context: Box::new(ErrorContext::selector("div.schedule tr")),
+        }
+    }
+
+    /// Create an [`Error::ScheduleMismatch`].
+    pub(crate) fn schedule_mismatch(label: &str, expected: NaiveDate) -> Self {
+        Self::ScheduleMismatch {
+            label: label.to_owned(),
+            expected,
+            context: Box::new(ErrorContext::selector("div.schedule tr")),
+        }
+    }
+
+    /// Get the [`ErrorContext`] of this error, if it has one.
+    #[must_use]
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            Self::ElementNotFound { context, .. }
+            | Self::TextNotFound { context, .. }
+            | Self::AttributeNotFound { context, .. }
+            | Self::SplitTime { context }
+            | Self::ParseTime { context, .. }
+            | Self::ParseScheduleLabel { context, .. }
+            | Self::ScheduleMismatch { context, .. }
+            | Self::Misc { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    fn context_mut(&mut self) -> Option<&mut ErrorContext> {
+        match self {
+            Self::ElementNotFound { context, .. }
+            | Self::TextNotFound { context, .. }
+            | Self::AttributeNotFound { context, .. }
+            | Self::SplitTime { context }
+            | Self::ParseTime { context, .. }
+            | Self::ParseScheduleLabel { context, .. }
+            | Self::ScheduleMismatch { context, .. }
+            | Self::Misc { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// Record the url of the page being parsed, if not already recorded.
+    #[must_use]
+    pub(crate) fn with_url(mut self, url: &Url) -> Self {
+        if let Some(context) = self.context_mut() {
+            context.url.get_or_insert_with(|| url.clone());
+        }
+
+        self
+    }
+
+    /// Record the selector of the element being parsed, if not already
+    /// recorded.
+    #[must_use]
+    pub(crate) fn with_selector(mut self, selector: &'static str) -> Self {
+        if let Some(context) = self.context_mut() {
+            context.selector.get_or_insert(selector);
+        }
+
+        self
+    }
+
+    /// Record the name of the restaurant or location being parsed, if not
+    /// already recorded.
+    #[must_use]
+    pub(crate) fn with_subject(mut self, subject: &str) -> Self {
+        if let Some(context) = self.context_mut() {
+            context.subject.get_or_insert_with(|| subject.to_owned());
+        }
+
+        self
+    }
+
+    /// Record an excerpt of the outer HTML of the element being parsed, if not
+    /// already recorded.
+    #[must_use]
+    pub(crate) fn with_element(mut self, element: ElementRef<'_>) -> Self {
+        if let Some(context) = self.context_mut() {
+            context.html.get_or_insert_with(|| {
+                let html = element.html();
+
+                match html.char_indices().nth(HTML_EXCERPT_LEN) {
+                    Some((i, _)) => format!("{}…", &html[..i]),
+                    None => html,
+                }
+            });
+        }
+
+        self
+    }
+}
+
+/// Where in MacEats a scraping [`enum@Error`] occurred.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct ErrorContext {
+    /// The url of the page being parsed.
+    pub url: Option<Url>,
+
+    /// The CSS selector that was being matched.
+    pub selector: Option<&'static str>,
+
+    /// The name of the restaurant or location being parsed.
+    pub subject: Option<String>,
+
+    /// A truncated excerpt of the outer HTML of the element being parsed.
+    pub html: Option<String>,
+}
+
+impl ErrorContext {
+    fn selector(selector: &'static str) -> Self {
+        Self {
+            selector: Some(selector),
+            ..Self::default()
+        }
+    }
+}
+
+impl Display for ErrorContext {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let parts = [
+            self.selector
+                .map(|selector| format!("selector `{selector}`")),
+            self.subject
+                .as_ref()
+                .map(|subject| format!("in {subject:?}")),
+            self.url.as_ref().map(|url| format!("at {url}")),
+            self.html.as_ref().map(|html| format!("html `{html}`")),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+
+        if parts.is_empty() {
+            Ok(())
+        } else {
+            write!(f, " ({})", parts.join(", "))
+        }
+    }
 }
 
 /// A convenience type representing a [`Result`] with the error type set to
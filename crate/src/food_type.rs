@@ -3,17 +3,27 @@ use std::{
     str::FromStr,
 };
 
+use futures::Stream;
+use once_cell::sync::Lazy;
 use reqwest::Url;
 use scraper::ElementRef;
 use serde::{Deserialize, Serialize};
 
-use crate::{Error, Restaurant, Result};
+use crate::{Error, Restaurant, Result, DEFAULT_CLIENT};
 
 /// The type of food served at a [`Restaurant`].
 ///
+/// Tags that MacEats has added since this crate was released are parsed as
+/// [`FoodType::Unknown`], so use [`FoodType::is_known`] to tell them apart.
+///
+/// A known [`FoodType`] is serialized in kebab case (e.g. `"gluten-free"`),
+/// and a [`FoodType::Unknown`] as the tag shown on MacEats. Both forms, and
+/// the tags of known food types, can be deserialized.
+///
 /// [`Restaurant`]: crate::Restaurant
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
-#[serde(rename_all = "kebab-case")]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+#[non_exhaustive]
 pub enum FoodType {
     /// Breakfast food.
     Breakfast,
@@ -62,43 +72,51 @@ pub enum FoodType {
 
     /// Vegetarian food.
     Vegetarian,
+
+    /// A food type this crate does not know about, holding the tag as shown
+    /// on MacEats.
+    Unknown(String),
 }
 
 impl FoodType {
-    /// Get the urls for this [`FoodType`].
+    /// Get the MacEats slug for this [`FoodType`], or [`None`] if MacEats has
+    /// no page for it.
     #[must_use]
-    pub fn url(&self) -> Option<Url> {
-        macro_rules! url {
-            ($slug:literal) => {
-                Some(
-                    concat!("https://maceats.mcmaster.ca/types/", $slug)
-                        .parse()
-                        .expect("static url should be valid"),
-                )
-            };
-        }
-
+    pub const fn slug(&self) -> Option<&'static str> {
         match self {
-            Self::Breakfast => url!("breakfast"),
-            Self::Coffee => None,
-            Self::Convenience => url!("convenience"),
-            Self::Dessert => url!("dessert"),
-            Self::GlutenFree => url!("gluten-free"),
-            Self::Grill => url!("grill"),
-            Self::Halal => url!("halal"),
-            Self::Kosher => url!("kosher"),
-            Self::Noodles => url!("noodles"),
-            Self::Pasta => url!("pasta"),
-            Self::Pizza => url!("pizza"),
-            Self::Sandwiches => url!("sandwiches"),
-            Self::Snacks => url!("snacks"),
-            Self::Soup => url!("soup"),
-            Self::Sushi => url!("sushi"),
-            Self::Vegetarian => url!("vegetarian"),
+            Self::Breakfast => Some("breakfast"),
+            Self::Coffee | Self::Unknown(_) => None,
+            Self::Convenience => Some("convenience"),
+            Self::Dessert => Some("dessert"),
+            Self::GlutenFree => Some("gluten-free"),
+            Self::Grill => Some("grill"),
+            Self::Halal => Some("halal"),
+            Self::Kosher => Some("kosher"),
+            Self::Noodles => Some("noodles"),
+            Self::Pasta => Some("pasta"),
+            Self::Pizza => Some("pizza"),
+            Self::Sandwiches => Some("sandwiches"),
+            Self::Snacks => Some("snacks"),
+            Self::Soup => Some("soup"),
+            Self::Sushi => Some("sushi"),
+            Self::Vegetarian => Some("vegetarian"),
         }
     }
 
-    /// Get every [`FoodType`].
+    /// Whether this [`FoodType`] is one this crate knows about, rather than a
+    /// [`FoodType::Unknown`] discovered on MacEats.
+    #[must_use]
+    pub const fn is_known(&self) -> bool {
+        !matches!(self, Self::Unknown(_))
+    }
+
+    /// Get the urls for this [`FoodType`].
+    #[must_use]
+    pub fn url(&self) -> Option<Url> {
+        DEFAULT_CLIENT.food_type_url(self)
+    }
+
+    /// Get every known [`FoodType`].
     #[must_use]
     pub const fn all() -> &'static [Self] {
         &[
@@ -125,8 +143,9 @@ impl FoodType {
     ///
     /// Note that this function will get, then filter all [`Restaurant`]s if
     /// `self` is [`FoodType::Coffee`], as MacEats provides no way to filter for
-    /// all coffee. To get the [`Restaurant`]s that serve a specific brand of
-    /// coffee, use [`CoffeeBrand::restaurants`].
+    /// all coffee, or if `self` is a [`FoodType::Unknown`]. To get the
+    /// [`Restaurant`]s that serve a specific brand of coffee, use
+    /// [`CoffeeBrand::restaurants`].
     ///
     /// # Errors
     ///
@@ -136,27 +155,36 @@ impl FoodType {
     /// [`Restaurant`]: crate::Restaurant
     /// [`CoffeeBrand::restaurants`]: crate::CoffeeBrand::restaurants
     pub async fn restaurants(&self) -> Result<Vec<Restaurant>> {
-        if let Some(url) = self.url() {
-            Restaurant::from_restaurant_list_url(url).await
-        } else {
-            Ok(Restaurant::all()
-                .await?
-                .into_iter()
-                .filter(|r| r.tags.contains(self))
-                .collect())
-        }
+        DEFAULT_CLIENT.food_type_restaurants(self).await
+    }
+
+    /// Get a [`Stream`] of the [`Restaurant`]s that serve this food type.
+    ///
+    /// If `self` is [`FoodType::Coffee`] or a [`FoodType::Unknown`], each
+    /// [`Restaurant`] is yielded as soon as its location's page has been
+    /// parsed.
+    ///
+    /// # Errors
+    ///
+    /// The stream will yield an error if sending a request or parsing a
+    /// response fails.
+    ///
+    /// [`Restaurant`]: crate::Restaurant
+    pub fn stream_restaurants(&self) -> impl Stream<Item = Result<Restaurant>> + '_ {
+        Lazy::force(&DEFAULT_CLIENT).food_type_restaurants_stream(self)
     }
 
     /// Get the [`Restaurant`]s that serve this food type, returning an empty
-    /// [`Vec<Restaurant>`] if `self` is [`FoodType::Coffee`].
+    /// [`Vec<Restaurant>`] if `self` is [`FoodType::Coffee`] or a
+    /// [`FoodType::Unknown`].
     ///
     /// # Errors
     ///
     /// This function will return an error if sending the request or parsing the
     /// response fails.
     pub async fn restaurants_no_coffee(&self) -> Result<Vec<Restaurant>> {
-        if let Some(url) = self.url() {
-            Restaurant::from_restaurant_list_url(url).await
+        if self.slug().is_some() {
+            DEFAULT_CLIENT.food_type_restaurants(self).await
         } else {
             Ok(Vec::new())
         }
@@ -182,6 +210,7 @@ impl Display for FoodType {
             Self::Soup => write!(f, "Soup"),
             Self::Sushi => write!(f, "Sushi"),
             Self::Vegetarian => write!(f, "Vegetarian"),
+            Self::Unknown(s) => write!(f, "{s}"),
         }
     }
 }
@@ -190,7 +219,7 @@ impl FromStr for FoodType {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self> {
-        match s {
+        match s.trim() {
             "Breakfast" | "breakfast" => Ok(Self::Breakfast),
             "Coffee" | "coffee" => Ok(Self::Coffee),
             "Convenience" | "convenience" => Ok(Self::Convenience),
@@ -207,7 +236,28 @@ impl FromStr for FoodType {
             "Soup" | "soup" => Ok(Self::Soup),
             "Sushi" | "sushi" => Ok(Self::Sushi),
             "Vegetarian" | "vegetarian" => Ok(Self::Vegetarian),
-            s => Err(Error::ParseFoodType(s.into())),
+            "" => Err(Error::ParseFoodType(s.into())),
+            s => Ok(Self::Unknown(s.to_owned())),
+        }
+    }
+}
+
+impl TryFrom<String> for FoodType {
+    type Error = Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<FoodType> for String {
+    fn from(food_type: FoodType) -> Self {
+        match food_type {
+            FoodType::Coffee => "coffee".to_owned(),
+            FoodType::Unknown(tag) => tag,
+            food_type => food_type
+                .slug()
+                .map_or_else(|| food_type.to_string(), str::to_owned),
         }
     }
 }
@@ -218,11 +268,52 @@ impl TryFrom<ElementRef<'_>> for FoodType {
     fn try_from(element: ElementRef<'_>) -> Result<Self> {
         debug_assert_eq!(element.value().name(), "li");
 
-        let text = element
-            .text()
-            .next()
-            .ok_or_else(|| Error::TextNotFound("food type"))?;
+        let text = element.text().next().ok_or_else(|| {
+            Error::text_not_found("food type", "ul.tags li").with_element(element)
+        })?;
 
         text.parse()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_trimmed_tags() {
+        assert_eq!(
+            " Breakfast ".parse::<FoodType>().unwrap(),
+            FoodType::Breakfast
+        );
+        assert_eq!(
+            "gluten-free".parse::<FoodType>().unwrap(),
+            FoodType::GlutenFree
+        );
+        assert_eq!(
+            " Bubble Tea ".parse::<FoodType>().unwrap(),
+            FoodType::Unknown("Bubble Tea".to_owned())
+        );
+        assert!(" ".parse::<FoodType>().is_err());
+    }
+
+    #[test]
+    fn serializes_known_food_types_as_slugs() {
+        let food_types = vec![
+            FoodType::GlutenFree,
+            FoodType::Coffee,
+            FoodType::Unknown("Bubble Tea".to_owned()),
+        ];
+        let json = serde_json::to_string(&food_types).unwrap();
+
+        assert_eq!(json, r#"["gluten-free","coffee","Bubble Tea"]"#);
+        assert_eq!(
+            serde_json::from_str::<Vec<FoodType>>(&json).unwrap(),
+            food_types
+        );
+        assert_eq!(
+            serde_json::from_str::<FoodType>(r#""Gluten Free""#).unwrap(),
+            FoodType::GlutenFree
+        );
+    }
+}
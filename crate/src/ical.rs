@@ -0,0 +1,229 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
+
+use crate::{Open, Restaurant, Times};
+
+/// The `VTIMEZONE` for `America/Toronto`, using the DST rules in effect since
+/// 2007.
+const VTIMEZONE: &[&str] = &[
+    "BEGIN:VTIMEZONE",
+    "TZID:America/Toronto",
+    "BEGIN:DAYLIGHT",
+    "TZOFFSETFROM:-0500",
+    "TZOFFSETTO:-0400",
+    "TZNAME:EDT",
+    "DTSTART:19700308T020000",
+    "RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=2SU",
+    "END:DAYLIGHT",
+    "BEGIN:STANDARD",
+    "TZOFFSETFROM:-0400",
+    "TZOFFSETTO:-0500",
+    "TZNAME:EST",
+    "DTSTART:19701101T020000",
+    "RRULE:FREQ=YEARLY;BYMONTH=11;BYDAY=1SU",
+    "END:STANDARD",
+    "END:VTIMEZONE",
+];
+
+/// The maximum length of a content line, in octets, before it is folded.
+const LINE_LEN: usize = 75;
+
+/// Write an RFC 5545 iCalendar document with an event for every range each
+/// restaurant is open on each date in its schedule.
+pub fn write<'a>(restaurants: impl IntoIterator<Item = &'a Restaurant>) -> String {
+    let dtstamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_owned(),
+        "VERSION:2.0".to_owned(),
+        format!(
+            "PRODID:-//maceats//maceats {}//EN",
+            env!("CARGO_PKG_VERSION")
+        ),
+        "CALSCALE:GREGORIAN".to_owned(),
+    ];
+
+    lines.extend(VTIMEZONE.iter().map(|&line| line.to_owned()));
+
+    for restaurant in restaurants {
+        for (&date, times) in restaurant.schedule.iter().flatten() {
+            if let Times::Open(opens) = times {
+                for (i, open) in opens.iter().enumerate() {
+                    lines.extend(event(restaurant, date, i, open, &dtstamp));
+                }
+            }
+        }
+    }
+
+    lines.push("END:VCALENDAR".to_owned());
+
+    lines.iter().fold(String::new(), |mut ics, line| {
+        fold(&mut ics, line);
+        ics
+    })
+}
+
+/// Get the lines of the `VEVENT` for a restaurant being open during `open`,
+/// the `i`th range of its times on `date`.
+fn event(
+    restaurant: &Restaurant,
+    date: NaiveDate,
+    i: usize,
+    open: &Open,
+    dtstamp: &str,
+) -> Vec<String> {
+    let local = |instant: DateTime<Tz>| instant.format("%Y%m%dT%H%M%S").to_string();
+
+    let location = restaurant.location_details.as_ref().map_or_else(
+        || restaurant.location.name.clone(),
+        |details| format!("{}, {details}", restaurant.location.name),
+    );
+
+    vec![
+        "BEGIN:VEVENT".to_owned(),
+        format!(
+            "UID:{}-{}-{i}@maceats.mcmaster.ca",
+            restaurant.id(),
+            date.format("%Y%m%d"),
+        ),
+        format!("DTSTAMP:{dtstamp}"),
+        format!(
+            "DTSTART;TZID=America/Toronto:{}",
+            local(open.start_on(date))
+        ),
+        format!("DTEND;TZID=America/Toronto:{}", local(open.end_on(date))),
+        format!("SUMMARY:{}", escape(&restaurant.name)),
+        format!("LOCATION:{}", escape(&location)),
+        "TRANSP:TRANSPARENT".to_owned(),
+        "END:VEVENT".to_owned(),
+    ]
+}
+
+/// Escape a `TEXT` value.
+fn escape(text: &str) -> String {
+    text.chars().fold(String::new(), |mut escaped, c| {
+        match c {
+            '\\' | ';' | ',' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+
+        escaped
+    })
+}
+
+/// Write a content line, folding it so that no line is longer than
+/// [`LINE_LEN`] octets, and ending each line with CRLF.
+fn fold(ics: &mut String, line: &str) {
+    let mut len = 0;
+
+    for c in line.chars() {
+        if len + c.len_utf8() > LINE_LEN {
+            ics.push_str("\r\n ");
+            len = 1;
+        }
+
+        ics.push(c);
+        len += c.len_utf8();
+    }
+
+    ics.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{location, schedule};
+
+    fn restaurant(times: &str) -> Restaurant {
+        Restaurant {
+            location_details: Some("Main floor, near the Bookstore".to_owned()),
+            schedule: Some(schedule([(20, times)])),
+            ..crate::test_util::restaurant(
+                "Tim Hortons",
+                &location(
+                    "McMaster University Student Centre",
+                    "mcmaster-university-student-centre",
+                ),
+            )
+        }
+    }
+
+    fn uids(ics: &str) -> Vec<String> {
+        ics.replace("\r\n ", "")
+            .lines()
+            .filter_map(|line| line.strip_prefix("UID:"))
+            .map(str::to_owned)
+            .collect()
+    }
+
+    #[test]
+    fn escapes_text() {
+        assert_eq!(escape("Tim Hortons"), "Tim Hortons");
+        assert_eq!(escape("Level 1, Room 2; East"), "Level 1\\, Room 2\\; East");
+        assert_eq!(escape("a\\b"), "a\\\\b");
+        assert_eq!(escape("first\r\nsecond"), "first\\nsecond");
+    }
+
+    #[test]
+    fn folds_long_lines() {
+        let mut ics = String::new();
+        fold(&mut ics, &"a".repeat(160));
+
+        let lines = ics.split_terminator("\r\n").collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].len(), 75);
+        assert_eq!(lines[1], format!(" {}", "a".repeat(74)));
+        assert_eq!(lines[2], format!(" {}", "a".repeat(11)));
+
+        let mut ics = String::new();
+        fold(&mut ics, "short");
+        assert_eq!(ics, "short\r\n");
+    }
+
+    #[test]
+    fn folds_between_characters() {
+        let mut ics = String::new();
+        fold(&mut ics, &"é".repeat(60));
+
+        for line in ics.split_terminator("\r\n") {
+            assert!(line.len() <= LINE_LEN);
+        }
+
+        assert_eq!(ics.replace("\r\n ", "").trim_end(), "é".repeat(60));
+    }
+
+    #[test]
+    fn writes_escaped_folded_events() {
+        let ics = write([&restaurant("7:30 am - 9 pm")]);
+
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert!(ics
+            .split_terminator("\r\n")
+            .all(|line| line.len() <= LINE_LEN));
+        assert!(ics.replace("\r\n ", "").contains(
+            "LOCATION:McMaster University Student Centre\\, Main floor\\, near the Bookstore\r\n"
+        ));
+        assert!(ics.contains("DTSTART;TZID=America/Toronto:20221020T073000\r\n"));
+        assert!(ics.contains("DTEND;TZID=America/Toronto:20221020T210000\r\n"));
+    }
+
+    #[test]
+    fn keeps_uids_when_times_change() {
+        let before = write([&restaurant("7:30 am - 2 pm, 4 pm - 9 pm")]);
+        let after = write([&restaurant("8 am - 2 pm, 5 pm - 10 pm")]);
+
+        assert_eq!(
+            uids(&before),
+            [
+                "mcmaster-university-student-centre:tim-hortons-20221020-0@maceats.mcmaster.ca",
+                "mcmaster-university-student-centre:tim-hortons-20221020-1@maceats.mcmaster.ca",
+            ]
+        );
+        assert_eq!(uids(&before), uids(&after));
+    }
+}
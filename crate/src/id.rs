@@ -0,0 +1,193 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Restaurant, Result};
+
+/// An identifier for a [`Restaurant`] that stays the same between scrapes.
+///
+/// It is made from the slug of the restaurant's location and a slug of its
+/// name, like `mcmaster-university-student-centre:tim-hortons`, so it does
+/// not change when the restaurant's hours or tags do.
+///
+/// The name slug is the name transliterated to ASCII and lowercased, with
+/// apostrophes removed and every other run of non-alphanumeric characters
+/// replaced by a `-` (so `Bridges Café` becomes `bridges-cafe` and
+/// `McDonald's` becomes `mcdonalds`).
+///
+/// Two restaurants with the same name at the same location have the same id,
+/// so functions that key restaurants by id return
+/// [`Error::RestaurantIdCollision`] if they find any.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct RestaurantId {
+    location_slug: String,
+    name_slug: String,
+}
+
+impl RestaurantId {
+    /// Create the [`RestaurantId`] of a restaurant named `name` at the location
+    /// with the slug `location_slug`.
+    #[must_use]
+    pub fn new(location_slug: &str, name: &str) -> Self {
+        Self {
+            location_slug: location_slug.to_owned(),
+            name_slug: slugify(name),
+        }
+    }
+
+    /// Get the slug of the restaurant's location.
+    #[must_use]
+    pub fn location_slug(&self) -> &str {
+        &self.location_slug
+    }
+
+    /// Get the slug of the restaurant's name.
+    #[must_use]
+    pub fn name_slug(&self) -> &str {
+        &self.name_slug
+    }
+
+    /// Check that no two restaurants in `restaurants` have the same
+    /// [`RestaurantId`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if two restaurants have the same
+    /// [`RestaurantId`], even if they have exactly the same name.
+    pub fn check_collisions(restaurants: &[Restaurant]) -> Result<()> {
+        let mut names = BTreeMap::new();
+
+        restaurants.iter().try_for_each(|restaurant| {
+            names
+                .insert(restaurant.id(), restaurant.name.as_str())
+                .map_or(Ok(()), |name| {
+                    Err(Error::RestaurantIdCollision {
+                        id: restaurant.id(),
+                        names: (name.to_owned(), restaurant.name.clone()),
+                    })
+                })
+        })
+    }
+}
+
+impl Display for RestaurantId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.location_slug, self.name_slug)
+    }
+}
+
+impl FromStr for RestaurantId {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.split_once(':') {
+            Some((location_slug, name_slug))
+                if !location_slug.is_empty()
+                    && !name_slug.is_empty()
+                    && slugify(name_slug) == name_slug =>
+            {
+                Ok(Self {
+                    location_slug: location_slug.to_owned(),
+                    name_slug: name_slug.to_owned(),
+                })
+            }
+            _ => Err(Error::ParseRestaurantId(s.to_owned())),
+        }
+    }
+}
+
+impl TryFrom<String> for RestaurantId {
+    type Error = Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<RestaurantId> for String {
+    fn from(id: RestaurantId) -> Self {
+        id.to_string()
+    }
+}
+
+/// Turn `s` into a slug of lowercase ASCII letters and digits separated by
+/// single `-`s.
+fn slugify(s: &str) -> String {
+    deunicode::deunicode(s)
+        .to_ascii_lowercase()
+        .replace('\'', "")
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::location;
+
+    #[test]
+    fn slugifies_names() {
+        assert_eq!(slugify("Tim Hortons"), "tim-hortons");
+        assert_eq!(slugify("Bridges Café"), "bridges-cafe");
+        assert_eq!(slugify("McDonald's"), "mcdonalds");
+        assert_eq!(slugify("McDonald’s"), "mcdonalds");
+        assert_eq!(slugify("iPotato"), "ipotato");
+        assert_eq!(slugify("  Pizza Pizza -- Express! "), "pizza-pizza-express");
+        assert_eq!(slugify("La Piazza (2nd Floor)"), "la-piazza-2nd-floor");
+    }
+
+    fn restaurant(name: &str, slug: &str) -> Restaurant {
+        crate::test_util::restaurant(name, &location(slug, slug))
+    }
+
+    #[test]
+    fn checks_collisions() {
+        let centre = "mcmaster-university-student-centre";
+
+        assert!(RestaurantId::check_collisions(&[
+            restaurant("Tim Hortons", centre),
+            restaurant("Tim Hortons", "mills"),
+            restaurant("Starbucks", centre),
+        ])
+        .is_ok());
+
+        assert!(matches!(
+            RestaurantId::check_collisions(&[
+                restaurant("Tim Hortons", centre),
+                restaurant("Tim Hortons", centre),
+            ]),
+            Err(Error::RestaurantIdCollision { .. })
+        ));
+
+        assert!(matches!(
+            RestaurantId::check_collisions(&[
+                restaurant("Bridges Café", centre),
+                restaurant("Bridges Cafe", centre),
+            ]),
+            Err(Error::RestaurantIdCollision { names, .. })
+                if names == ("Bridges Café".to_owned(), "Bridges Cafe".to_owned())
+        ));
+    }
+
+    #[test]
+    fn parses_only_slugs() {
+        let id: RestaurantId = "mcmaster-university-student-centre:tim-hortons"
+            .parse()
+            .unwrap();
+
+        assert_eq!(id.location_slug(), "mcmaster-university-student-centre");
+        assert_eq!(id.name_slug(), "tim-hortons");
+        assert_eq!(id.to_string().parse::<RestaurantId>().unwrap(), id);
+
+        assert!("tim-hortons".parse::<RestaurantId>().is_err());
+        assert!("centre:".parse::<RestaurantId>().is_err());
+        assert!("centre:Tim Hortons".parse::<RestaurantId>().is_err());
+    }
+}
@@ -16,24 +16,54 @@
 #![allow(clippy::multiple_crate_versions)]
 #![allow(clippy::use_self)]
 
+mod changes;
+mod client;
 mod coffee_brand;
 mod error;
 mod food_type;
+mod ical;
+mod id;
 mod location;
+mod mirror;
+mod query;
+mod report;
+mod retry;
+mod schedule;
+mod search;
+mod snapshot;
+mod status;
+mod summary;
 mod times;
 
+#[cfg(test)]
+mod test_util;
+
 mod restaurant;
 
+use chrono_tz::Tz;
 use once_cell::sync::Lazy;
-use reqwest::Client;
 
+pub use changes::{Change, ChangeSet, RestaurantChanges};
+pub use client::{MacEatsClient, MacEatsClientBuilder};
 pub use coffee_brand::CoffeeBrand;
-pub use error::{Error, Result};
+pub use error::{Error, ErrorContext, Result};
 pub use food_type::FoodType;
-pub use location::Location;
+pub use id::RestaurantId;
+pub use location::{Location, LocationResolver};
+pub use mirror::{MirrorManifest, MirrorPage};
+pub use query::{FoodTypeMatch, RestaurantQuery, SortBy};
+pub use report::{ParseFailure, ParseReport};
 pub use restaurant::Restaurant;
+pub use retry::RetryPolicy;
+pub use search::SearchResult;
+pub use snapshot::Snapshot;
+pub use status::{OpenNowDisagreement, OpenNowReconciliation, OpenStatus};
+pub use summary::{DayGroup, WeeklySummary};
 pub use times::{Open, Times};
 
+/// The timezone of McMaster's campus, which MacEats schedules are given in.
+pub const TIMEZONE: Tz = chrono_tz::America::Toronto;
+
 macro_rules! selector {
     ($selector:literal $(,)?) => {{
         static SELECTOR: ::once_cell::sync::OnceCell<::scraper::Selector> =
@@ -54,4 +84,6 @@ macro_rules! regex {
 }
 pub(crate) use regex;
 
-pub(crate) static CLIENT: Lazy<Client> = Lazy::new(Client::new);
+/// The [`MacEatsClient`] used by the convenience functions on [`Restaurant`],
+/// [`Location`], [`FoodType`] and [`CoffeeBrand`].
+pub(crate) static DEFAULT_CLIENT: Lazy<MacEatsClient> = Lazy::new(MacEatsClient::new);
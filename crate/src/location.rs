@@ -11,7 +11,10 @@ use scraper::{ElementRef, Html};
 use selectors::attr::CaseSensitivity;
 use serde::{Deserialize, Serialize};
 
-use crate::{selector, Error, Restaurant, Result, CLIENT};
+use crate::{
+    search::{self, SearchResult},
+    selector, Error, ParseReport, Restaurant, Result, DEFAULT_CLIENT,
+};
 
 /// A location where [`Restaurant`]s are located.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
@@ -24,7 +27,10 @@ pub struct Location {
 }
 
 impl Location {
-    /// Create a new [`Location`].
+    /// Create a new [`Location`], guessing its slug from its name.
+    ///
+    /// The guessed slug is not always correct. Use [`Location::from_slug`] or a
+    /// [`LocationResolver`] to get a location as listed on MacEats.
     #[must_use]
     pub fn new(name: &str) -> Self {
         Self {
@@ -41,11 +47,7 @@ impl Location {
     /// Get the maceats url of this location.
     #[must_use]
     pub fn url(&self) -> Url {
-        "https://maceats.mcmaster.ca/locations/"
-            .parse::<Url>()
-            .expect("static url should be valid")
-            .join(&self.slug)
-            .expect("slug should be valid")
+        DEFAULT_CLIENT.location_url(self)
     }
 
     /// Get every location on MacEats.
@@ -54,12 +56,17 @@ impl Location {
     ///
     /// This function will return an error if the request fails.
     pub async fn all() -> Result<Vec<Self>> {
-        Self::from_location_list_url(
-            "https://maceats.mcmaster.ca/locations"
-                .parse()
-                .expect("static url should be valid"),
-        )
-        .await
+        DEFAULT_CLIENT.locations().await
+    }
+
+    /// Get the location on MacEats with the slug `slug`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if sending the request or parsing the
+    /// response fails, or if no location has the slug `slug`.
+    pub async fn from_slug(slug: &str) -> Result<Self> {
+        DEFAULT_CLIENT.location(slug).await
     }
 
     /// Parse a location list into a [`Vec<Location>`].
@@ -69,10 +76,7 @@ impl Location {
     /// This function will return an error if sending the request or parsing the
     /// response fails.
     pub async fn from_location_list_url(url: Url) -> Result<Vec<Self>> {
-        let response = CLIENT.get(url).send().await?.error_for_status()?;
-        let html = Html::parse_document(&response.text().await?);
-
-        Self::from_location_list_html(&html)
+        DEFAULT_CLIENT.location_list(url).await
     }
 
     /// Parse a location list [`Html`] document into a [`Vec<Location>`].
@@ -87,6 +91,40 @@ impl Location {
             .collect()
     }
 
+    /// Leniently parse a location list [`Html`] document into a
+    /// [`ParseReport<Location>`], skipping locations that fail to parse instead
+    /// of failing the whole list.
+    #[must_use]
+    pub fn from_location_list_html_lenient(html: &Html) -> ParseReport<Self> {
+        ParseReport::from_elements(
+            html.select(selector!("div.unit.unit-location")),
+            Self::try_from,
+            |element| {
+                Some(
+                    element
+                        .select(selector!("a"))
+                        .next()?
+                        .text()
+                        .next()?
+                        .trim()
+                        .to_owned(),
+                )
+            },
+        )
+    }
+
+    /// Search `locations` for `query`, matching their names and allowing for
+    /// typos and building codes (e.g. `MUSC`).
+    ///
+    /// Results are ordered from best to worst match, and poor matches are left
+    /// out.
+    #[must_use]
+    pub fn search<'a>(locations: &'a [Self], query: &str) -> Vec<SearchResult<'a, Self>> {
+        search::search(locations, query, |location| {
+            vec![(location.name.as_str(), 1.0), (location.slug.as_str(), 1.0)]
+        })
+    }
+
     /// Get the [`Restaurant`]s at this [`Location`].
     ///
     /// # Errors
@@ -94,10 +132,95 @@ impl Location {
     /// This function will return an error if sending the request or parsing the
     /// response fails.
     pub async fn restaurants(&self) -> Result<Vec<Restaurant>> {
-        let response = CLIENT.get(self.url()).send().await?;
-        let html = Html::parse_document(&response.text().await?);
+        DEFAULT_CLIENT.location_restaurants(self).await
+    }
+}
+
+/// Maps location names, as shown on restaurant cards, onto the [`Location`]s
+/// listed on MacEats.
+///
+/// Restaurant cards only give the name of their location, so the
+/// [`Restaurant::location`] parsed from a card has a slug guessed by
+/// [`Location::new`]. A [`LocationResolver`] replaces it with the listed
+/// location, or fails if none matches.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LocationResolver {
+    locations: Vec<Location>,
+}
+
+impl LocationResolver {
+    /// Create a new [`LocationResolver`] from the locations listed on MacEats.
+    #[must_use]
+    pub const fn new(locations: Vec<Location>) -> Self {
+        Self { locations }
+    }
+
+    /// Create a new [`LocationResolver`] from every location on MacEats.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if sending the request or parsing the
+    /// response fails.
+    pub async fn fetch() -> Result<Self> {
+        DEFAULT_CLIENT.location_resolver().await
+    }
+
+    /// Get the locations this [`LocationResolver`] resolves to.
+    #[must_use]
+    pub fn locations(&self) -> &[Location] {
+        &self.locations
+    }
 
-        Restaurant::from_restaurant_list_html(&html)
+    /// Get the location with the slug `slug`, if there is one.
+    #[must_use]
+    pub fn by_slug(&self, slug: &str) -> Option<&Location> {
+        self.locations.iter().find(|location| location.slug == slug)
+    }
+
+    /// Get the location with the name `name`.
+    ///
+    /// Names are compared ignoring case, punctuation and spacing. The slug
+    /// guessed from `name` is never used, as it may belong to a different
+    /// location.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if no location, or more than one
+    /// location, matches `name`.
+    pub fn resolve(&self, name: &str) -> Result<&Location> {
+        let key = |name: &str| {
+            name.chars()
+                .filter(|c| c.is_alphanumeric())
+                .flat_map(char::to_lowercase)
+                .collect::<String>()
+        };
+
+        let name_key = key(name);
+
+        let mut matches = self
+            .locations
+            .iter()
+            .filter(|location| key(&location.name) == name_key);
+
+        match (matches.next(), matches.next()) {
+            (Some(location), None) => Ok(location),
+            _ => Err(Error::UnresolvedLocation(name.trim().to_owned())),
+        }
+    }
+
+    /// Replace the guessed location of each of `restaurants` with the
+    /// location listed on MacEats.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the location of any restaurant
+    /// cannot be resolved.
+    pub fn resolve_restaurants(&self, restaurants: &mut [Restaurant]) -> Result<()> {
+        restaurants.iter_mut().try_for_each(|restaurant| {
+            restaurant.location = self.resolve(&restaurant.location.name)?.clone();
+
+            Ok(())
+        })
     }
 }
 
@@ -107,6 +230,10 @@ impl Display for Location {
     }
 }
 
+/// Parses a location name, guessing its slug like [`Location::new`].
+///
+/// The guessed location is not checked against MacEats, and may not exist. Use
+/// [`LocationResolver::resolve`] to find the location listed under a name.
 impl FromStr for Location {
     type Err = Error;
 
@@ -118,7 +245,7 @@ impl FromStr for Location {
 impl TryFrom<ElementRef<'_>> for Location {
     type Error = Error;
 
-    fn try_from(mut element: ElementRef<'_>) -> Result<Self> {
+    fn try_from(element: ElementRef<'_>) -> Result<Self> {
         debug_assert_eq!(element.value().name(), "div");
         debug_assert!(element
             .value()
@@ -127,30 +254,77 @@ impl TryFrom<ElementRef<'_>> for Location {
             .value()
             .has_class("unit-location", CaseSensitivity::CaseSensitive));
 
-        element = element
+        let link = element
             .select(selector!("a"))
             .next()
-            .ok_or_else(|| Error::ElementNotFound("location"))?;
+            .ok_or_else(|| Error::element_not_found("location", "a").with_element(element))?;
 
-        let name = element
+        let name = link
             .text()
             .next()
-            .ok_or_else(|| Error::TextNotFound("location"))?
+            .ok_or_else(|| Error::text_not_found("location", "a").with_element(element))?
             .trim()
             .to_owned();
 
-        let slug = Path::new(
-            &element
-                .value()
-                .attr("href")
-                .ok_or_else(|| Error::AttributeNotFound("location"))?,
-        )
-        .file_name()
-        .ok_or_else(|| Error::AttributeNotFound("location"))?
-        .to_str()
-        .ok_or_else(|| Error::AttributeNotFound("location"))?
-        .to_owned();
+        let slug_not_found = || {
+            Error::attribute_not_found("location href", "a")
+                .with_subject(&name)
+                .with_element(element)
+        };
+
+        let slug = Path::new(link.value().attr("href").ok_or_else(slug_not_found)?)
+            .file_name()
+            .ok_or_else(slug_not_found)?
+            .to_str()
+            .ok_or_else(slug_not_found)?
+            .to_owned();
 
         Ok(Self { name, slug })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::location;
+
+    #[test]
+    fn resolves_by_name() {
+        let resolver = LocationResolver::new(vec![
+            location(
+                "McMaster University Student Centre",
+                "mcmaster-university-student-centre",
+            ),
+            location("Mills Library", "mills"),
+        ]);
+
+        assert_eq!(
+            resolver
+                .resolve("mcmaster university student-centre")
+                .unwrap()
+                .slug,
+            "mcmaster-university-student-centre"
+        );
+        assert_eq!(resolver.resolve(" Mills Library ").unwrap().slug, "mills");
+    }
+
+    #[test]
+    fn does_not_resolve_by_guessed_slug() {
+        let resolver = LocationResolver::new(vec![location("Centro", "commons")]);
+
+        assert!(matches!(
+            resolver.resolve("Commons"),
+            Err(Error::UnresolvedLocation(name)) if name == "Commons"
+        ));
+    }
+
+    #[test]
+    fn does_not_resolve_ambiguous_names() {
+        let resolver = LocationResolver::new(vec![
+            location("Commons", "commons"),
+            location("Commons!", "commons-2"),
+        ]);
+
+        assert!(resolver.resolve("Commons").is_err());
+    }
+}
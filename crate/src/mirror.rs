@@ -0,0 +1,299 @@
+use std::{
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use futures::{stream, StreamExt, TryStreamExt};
+use reqwest::Url;
+use scraper::Html;
+use serde::{Deserialize, Serialize};
+
+use crate::{CoffeeBrand, Error, FoodType, Location, MacEatsClient, Result, DEFAULT_CLIENT};
+
+/// The name of the manifest file in a mirror directory.
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// The manifest of a mirror of MacEats saved to disk, recording every page
+/// saved and when it was fetched.
+///
+/// A mirror is written by [`MacEatsClient::mirror_to`], and can be read back
+/// by a [`MacEatsClient`] built with [`MacEatsClientBuilder::mirror`].
+///
+/// [`MacEatsClientBuilder::mirror`]: crate::MacEatsClientBuilder::mirror
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MirrorManifest {
+    /// The base url the pages were fetched relative to.
+    pub base_url: Url,
+
+    /// Every page in the mirror.
+    pub pages: Vec<MirrorPage>,
+}
+
+/// A page saved in a mirror of MacEats.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MirrorPage {
+    /// The url the page was fetched from.
+    pub url: Url,
+
+    /// The path of the page, relative to the mirror directory.
+    pub path: PathBuf,
+
+    /// When the page was fetched.
+    pub fetched_at: DateTime<Utc>,
+}
+
+impl MirrorManifest {
+    /// Mirror MacEats into the directory `dir`.
+    ///
+    /// See [`MacEatsClient::mirror_to`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if sending a request, parsing a
+    /// response or writing to `dir` fails.
+    pub async fn fetch(dir: impl AsRef<Path> + Send) -> Result<Self> {
+        DEFAULT_CLIENT.mirror_to(dir).await
+    }
+
+    /// Read the manifest of the mirror in the directory `dir`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the manifest cannot be read or is
+    /// not a valid manifest.
+    pub async fn read(dir: impl AsRef<Path> + Send) -> Result<Self> {
+        let json = tokio::fs::read_to_string(dir.as_ref().join(MANIFEST_FILE)).await?;
+
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// Get the page in the mirror fetched from `url`.
+    #[must_use]
+    pub fn page(&self, url: &Url) -> Option<&MirrorPage> {
+        self.pages.iter().find(|page| &page.url == url)
+    }
+
+    /// Mirror MacEats into the directory `dir` with `client`.
+    pub(crate) async fn fetch_with(client: &MacEatsClient, dir: &Path) -> Result<Self> {
+        let base_url = client.base_url();
+
+        let (locations_page, locations_body) = save(client, dir, client.locations_url()).await?;
+        let locations = Location::from_location_list_html(&Html::parse_document(&locations_body))
+            .map_err(|e| e.with_url(&locations_page.url))?;
+
+        let (coffee_brands_page, coffee_brands_body) =
+            save(client, dir, client.coffee_brands_url()).await?;
+        let coffee_brands =
+            CoffeeBrand::from_coffee_brand_list_html(&Html::parse_document(&coffee_brands_body))
+                .map_err(|e| e.with_url(&coffee_brands_page.url))?;
+
+        let urls = locations
+            .iter()
+            .map(|location| client.location_url(location))
+            .chain([client.open_now_url()])
+            .chain(
+                FoodType::all()
+                    .iter()
+                    .filter_map(|food_type| client.food_type_url(food_type)),
+            )
+            .chain(
+                coffee_brands
+                    .iter()
+                    .map(|coffee_brand| client.coffee_brand_url(coffee_brand)),
+            )
+            .collect::<Vec<_>>();
+
+        let pages = stream::iter(urls)
+            .map(|url| async move { save(client, dir, url).await.map(|(page, _)| page) })
+            .buffered(client.concurrency())
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        let manifest = Self {
+            base_url: base_url.clone(),
+            pages: [locations_page, coffee_brands_page]
+                .into_iter()
+                .chain(pages)
+                .collect(),
+        };
+
+        tokio::fs::write(
+            dir.join(MANIFEST_FILE),
+            serde_json::to_string_pretty(&manifest)?,
+        )
+        .await?;
+
+        Ok(manifest)
+    }
+}
+
+/// Fetch the page at `url` with `client` and save it into the mirror directory
+/// `dir`, returning the saved page and its body.
+///
+/// If `client` itself reads from a mirror, the page keeps the time it was
+/// originally fetched.
+async fn save(client: &MacEatsClient, dir: &Path, url: Url) -> Result<(MirrorPage, String)> {
+    let path = page_path(client.base_url(), &url).ok_or_else(|| Error::NotMirrored(url.clone()))?;
+    let (body, fetched_at) = client.fetch_page(url.clone()).await?;
+
+    let file = dir.join(&path);
+
+    if let Some(parent) = file.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+
+    tokio::fs::write(file, &body).await?;
+
+    log::debug!("mirrored {url} to {}", path.display());
+
+    Ok((
+        MirrorPage {
+            url,
+            path,
+            fetched_at,
+        },
+        body,
+    ))
+}
+
+/// A mirror of MacEats that a [`MacEatsClient`] reads pages from.
+#[derive(Debug)]
+pub struct Mirror {
+    /// The mirror directory.
+    dir: PathBuf,
+
+    /// The mirror's manifest.
+    manifest: MirrorManifest,
+}
+
+impl Mirror {
+    /// Open the mirror in the directory `dir`, reading its manifest.
+    pub fn open(dir: PathBuf) -> Result<Self> {
+        let json = std::fs::read_to_string(dir.join(MANIFEST_FILE))?;
+
+        Ok(Self {
+            dir,
+            manifest: serde_json::from_str(&json)?,
+        })
+    }
+
+    /// Get the mirror directory.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Read the body of the page at `url`, where `base_url` is the base url of
+    /// the client reading it, along with when the page was fetched.
+    pub async fn read(&self, base_url: &Url, url: &Url) -> Result<(String, DateTime<Utc>)> {
+        let not_mirrored = || Error::NotMirrored(url.clone());
+
+        let path = page_path(base_url, url).ok_or_else(not_mirrored)?;
+        let page = self
+            .manifest
+            .pages
+            .iter()
+            .find(|page| page.path == path)
+            .ok_or_else(not_mirrored)?;
+
+        match tokio::fs::read_to_string(self.dir.join(&page.path)).await {
+            Ok(body) => Ok((body, page.fetched_at)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Err(not_mirrored()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Get the path of the page at `url` relative to a mirror directory, or
+/// [`None`] if `url` is not under `base_url`.
+///
+/// `locations/foo` is saved as `locations/foo.html`, and the base url itself as
+/// `index.html`.
+fn page_path(base_url: &Url, url: &Url) -> Option<PathBuf> {
+    let relative = base_url.make_relative(url)?;
+    let relative = relative.split(['?', '#']).next()?.trim_matches('/');
+
+    if relative.split('/').any(|segment| segment == "..") {
+        return None;
+    }
+
+    Some(if relative.is_empty() {
+        PathBuf::from("index.html")
+    } else {
+        PathBuf::from(format!("{relative}.html"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::test_util::date;
+
+    const LOCATION_HTML: &str = r#"<html><body><div class="unit">
+        <h1 class="title">Centro</h1>
+        <h2 class="location">Commons Building</h2>
+        <div class="schedule"><table>
+            <tr><td class="day">Today</td><td class="time">7:30 am - 9 pm</td></tr>
+            <tr><td class="day">Tomorrow</td><td class="time">7:30 am - 9 pm</td></tr>
+            <tr><td class="day">Sat Oct 22</td><td class="time">Closed</td></tr>
+        </table></div>
+    </div></body></html>"#;
+
+    /// Write a mirror of a single location page, fetched at `fetched_at`.
+    fn write_mirror(name: &str, fetched_at: DateTime<Utc>) -> (PathBuf, Url) {
+        let dir = std::env::temp_dir().join(format!("maceats-{name}-{}", std::process::id()));
+        let base_url = default_client().base_url().clone();
+        let url = base_url.join("locations/commons-building").unwrap();
+        let path = page_path(&base_url, &url).unwrap();
+
+        std::fs::create_dir_all(dir.join(path.parent().unwrap())).unwrap();
+        std::fs::write(dir.join(&path), LOCATION_HTML).unwrap();
+
+        let manifest = MirrorManifest {
+            base_url,
+            pages: vec![MirrorPage {
+                url: url.clone(),
+                path,
+                fetched_at,
+            }],
+        };
+
+        std::fs::write(
+            dir.join(MANIFEST_FILE),
+            serde_json::to_string(&manifest).unwrap(),
+        )
+        .unwrap();
+
+        (dir, url)
+    }
+
+    fn default_client() -> MacEatsClient {
+        MacEatsClient::builder().build().unwrap()
+    }
+
+    #[tokio::test]
+    async fn dates_schedules_from_fetched_at() {
+        // Late on Oct 20 on campus, which is already Oct 21 in UTC.
+        let fetched_at = Utc.with_ymd_and_hms(2022, 10, 21, 2, 0, 0).unwrap();
+        let (dir, url) = write_mirror("fetched-at", fetched_at);
+
+        let client = MacEatsClient::builder().mirror(&dir).build().unwrap();
+        let result = client.restaurant_list(url.clone()).await;
+        let missing = client
+            .restaurant_list(client.base_url().join("locations/missing").unwrap())
+            .await;
+
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let restaurants = result.unwrap();
+        let schedule = restaurants[0].schedule.as_ref().unwrap();
+
+        assert_eq!(
+            schedule.keys().copied().collect::<Vec<_>>(),
+            [date(20), date(21), date(22)]
+        );
+        assert!(matches!(missing, Err(Error::NotMirrored(_))));
+    }
+}
@@ -0,0 +1,355 @@
+use std::{
+    borrow::Borrow,
+    collections::{BTreeMap, BTreeSet},
+};
+
+use chrono::{DateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use futures::future;
+use reqwest::Url;
+
+use crate::{
+    CoffeeBrand, FoodType, Location, MacEatsClient, Restaurant, RestaurantId, Result,
+    DEFAULT_CLIENT, TIMEZONE,
+};
+
+/// How a [`RestaurantQuery`] matches [`FoodType`]s.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum FoodTypeMatch {
+    /// Match restaurants that serve every food type.
+    #[default]
+    All,
+
+    /// Match restaurants that serve any of the food types.
+    Any,
+}
+
+/// How a [`RestaurantQuery`] sorts the restaurants it matches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SortBy {
+    /// Sort by name, then by location.
+    Name,
+
+    /// Sort by location, then by name.
+    Location,
+
+    /// Sort by when the restaurant next closes after the query's
+    /// [`open_at`] instant (or now), soonest first, with restaurants whose
+    /// closing time is unknown last.
+    ///
+    /// [`open_at`]: RestaurantQuery::open_at
+    ClosingTime,
+}
+
+/// A filter over [`Restaurant`]s, built up one condition at a time.
+///
+/// A query can be run against restaurants that were already fetched with
+/// [`RestaurantQuery::filter`], or fetch only the MacEats pages it needs with
+/// [`RestaurantQuery::fetch`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RestaurantQuery {
+    food_types: Vec<FoodType>,
+    food_type_match: FoodTypeMatch,
+    locations: Vec<Location>,
+    coffee_brands: Vec<CoffeeBrand>,
+    open_at: Option<DateTime<Tz>>,
+    name_contains: Option<String>,
+    sort_by: Option<SortBy>,
+}
+
+impl RestaurantQuery {
+    /// Create a new [`RestaurantQuery`] that matches every restaurant.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Match restaurants that serve a [`FoodType`].
+    ///
+    /// If more than one food type is given, they are matched according to
+    /// [`RestaurantQuery::food_type_match`].
+    #[must_use]
+    pub fn food_type(mut self, food_type: FoodType) -> Self {
+        self.food_types.push(food_type);
+        self
+    }
+
+    /// Match restaurants that serve several [`FoodType`]s.
+    #[must_use]
+    pub fn food_types(mut self, food_types: impl IntoIterator<Item = FoodType>) -> Self {
+        self.food_types.extend(food_types);
+        self
+    }
+
+    /// Set whether restaurants must serve all or any of the food types.
+    ///
+    /// By default, they must serve all of them.
+    #[must_use]
+    pub const fn food_type_match(mut self, food_type_match: FoodTypeMatch) -> Self {
+        self.food_type_match = food_type_match;
+        self
+    }
+
+    /// Match restaurants at a [`Location`], by its slug.
+    ///
+    /// If more than one location is given, restaurants at any of them are
+    /// matched.
+    #[must_use]
+    pub fn location(mut self, location: &Location) -> Self {
+        self.locations.push(location.clone());
+        self
+    }
+
+    /// Match restaurants that serve a [`CoffeeBrand`].
+    ///
+    /// If more than one coffee brand is given, restaurants serving any of them
+    /// are matched.
+    ///
+    /// When filtering restaurants that were already fetched, this uses
+    /// [`Restaurant::coffee_brands`].
+    #[must_use]
+    pub fn coffee_brand(mut self, coffee_brand: CoffeeBrand) -> Self {
+        self.coffee_brands.push(coffee_brand);
+        self
+    }
+
+    /// Match restaurants that are open at `instant`, according to their
+    /// schedules.
+    #[must_use]
+    pub fn open_at<T: TimeZone>(mut self, instant: &DateTime<T>) -> Self {
+        self.open_at = Some(instant.with_timezone(&TIMEZONE));
+        self
+    }
+
+    /// Match restaurants whose name contains `name`, ignoring case.
+    #[must_use]
+    pub fn name_contains(mut self, name: impl Into<String>) -> Self {
+        self.name_contains = Some(name.into());
+        self
+    }
+
+    /// Sort the matched restaurants.
+    ///
+    /// By default, restaurants are kept in the order they were given or
+    /// fetched in.
+    #[must_use]
+    pub const fn sort_by(mut self, sort_by: SortBy) -> Self {
+        self.sort_by = Some(sort_by);
+        self
+    }
+
+    /// Whether `restaurant` matches this query.
+    #[must_use]
+    pub fn matches(&self, restaurant: &Restaurant) -> bool {
+        let food_types = match self.food_type_match {
+            FoodTypeMatch::All => self
+                .food_types
+                .iter()
+                .all(|food_type| restaurant.tags.contains(food_type)),
+            FoodTypeMatch::Any => {
+                self.food_types.is_empty()
+                    || self
+                        .food_types
+                        .iter()
+                        .any(|food_type| restaurant.tags.contains(food_type))
+            }
+        };
+
+        food_types
+            && (self.locations.is_empty()
+                || self
+                    .locations
+                    .iter()
+                    .any(|location| location.slug == restaurant.location.slug))
+            && (self.coffee_brands.is_empty()
+                || self
+                    .coffee_brands
+                    .iter()
+                    .any(|coffee_brand| restaurant.coffee_brands.contains(coffee_brand)))
+            && self
+                .open_at
+                .is_none_or(|instant| restaurant.is_open_at(&instant))
+            && self.name_contains.as_ref().is_none_or(|name| {
+                restaurant
+                    .name
+                    .to_lowercase()
+                    .contains(&name.to_lowercase())
+            })
+    }
+
+    /// Get the restaurants in `restaurants` that match this query, sorted if
+    /// requested.
+    #[must_use]
+    pub fn filter<'a>(&self, restaurants: &'a [Restaurant]) -> Vec<&'a Restaurant> {
+        let mut restaurants = restaurants
+            .iter()
+            .filter(|restaurant| self.matches(restaurant))
+            .collect::<Vec<_>>();
+
+        self.sort(&mut restaurants);
+
+        restaurants
+    }
+
+    /// Fetch the restaurants on MacEats that match this query, sorted if
+    /// requested.
+    ///
+    /// See [`MacEatsClient::query`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if sending a request or parsing a
+    /// response fails.
+    pub async fn fetch(&self) -> Result<Vec<Restaurant>> {
+        DEFAULT_CLIENT.query(self).await
+    }
+
+    /// Fetch the restaurants that match this query with `client`, fetching
+    /// only the pages needed.
+    pub(crate) async fn fetch_with(&self, client: &MacEatsClient) -> Result<Vec<Restaurant>> {
+        let food_type_urls = self.food_type_urls(client);
+
+        let resolver = if !self.coffee_brands.is_empty()
+            || (self.locations.is_empty() && food_type_urls.is_some())
+        {
+            Some(client.location_resolver().await?)
+        } else {
+            None
+        };
+
+        let fetch_lists = |urls: Vec<Url>| async {
+            let lists =
+                future::try_join_all(urls.into_iter().map(|url| client.restaurant_list(url)))
+                    .await?;
+
+            let mut restaurants = dedup(lists)?;
+
+            if let Some(resolver) = &resolver {
+                resolver.resolve_restaurants(&mut restaurants)?;
+            }
+
+            Result::Ok(restaurants)
+        };
+
+        let brand_lists = future::try_join_all(
+            self.coffee_brands
+                .iter()
+                .map(|coffee_brand| fetch_lists(vec![client.coffee_brand_url(coffee_brand)])),
+        )
+        .await?;
+
+        let mut brand_membership = BTreeMap::<_, BTreeSet<_>>::new();
+
+        for (coffee_brand, restaurants) in self.coffee_brands.iter().zip(&brand_lists) {
+            for restaurant in restaurants {
+                brand_membership
+                    .entry(restaurant.id())
+                    .or_default()
+                    .insert(coffee_brand.clone());
+            }
+        }
+
+        let restaurants = if !self.locations.is_empty() {
+            dedup(
+                future::try_join_all(
+                    self.locations
+                        .iter()
+                        .map(|location| client.location_restaurants(location)),
+                )
+                .await?,
+            )?
+        } else if !brand_lists.is_empty() {
+            dedup(brand_lists)?
+        } else if let Some(urls) = food_type_urls {
+            fetch_lists(urls).await?
+        } else {
+            client.restaurants().await?
+        };
+
+        let mut restaurants = restaurants
+            .into_iter()
+            .map(|mut restaurant| {
+                if let Some(coffee_brands) = brand_membership.get(&restaurant.id()) {
+                    restaurant
+                        .coffee_brands
+                        .extend(coffee_brands.iter().cloned());
+                }
+
+                restaurant
+            })
+            .filter(|restaurant| self.matches(restaurant))
+            .collect::<Vec<_>>();
+
+        self.sort(&mut restaurants);
+
+        Ok(restaurants)
+    }
+
+    /// Get the urls of the food type pages that list every restaurant this
+    /// query could match, if there are any.
+    fn food_type_urls(&self, client: &MacEatsClient) -> Option<Vec<Url>> {
+        let urls = self
+            .food_types
+            .iter()
+            .map(|food_type| client.food_type_url(food_type));
+
+        let urls = match self.food_type_match {
+            FoodTypeMatch::All => urls.flatten().next().map(|url| vec![url]),
+            FoodTypeMatch::Any => urls.collect(),
+        };
+
+        urls.filter(|urls| !urls.is_empty())
+    }
+
+    /// Sort `restaurants` as requested.
+    fn sort<R: Borrow<Restaurant>>(&self, restaurants: &mut [R]) {
+        let by_name = |a: &Restaurant, b: &Restaurant| {
+            a.name
+                .cmp(&b.name)
+                .then_with(|| a.location.name.cmp(&b.location.name))
+        };
+
+        match self.sort_by {
+            Some(SortBy::Name) => restaurants.sort_by(|a, b| by_name(a.borrow(), b.borrow())),
+            Some(SortBy::Location) => restaurants.sort_by(|a, b| {
+                let (a, b) = (a.borrow(), b.borrow());
+
+                a.location
+                    .name
+                    .cmp(&b.location.name)
+                    .then_with(|| a.name.cmp(&b.name))
+            }),
+            Some(SortBy::ClosingTime) => {
+                let instant = self
+                    .open_at
+                    .unwrap_or_else(|| Utc::now().with_timezone(&TIMEZONE));
+
+                restaurants.sort_by_cached_key(|r| {
+                    let closing = r.borrow().next_closing(&instant);
+
+                    (closing.is_none(), closing, r.borrow().name.clone())
+                });
+            }
+            None => {}
+        }
+    }
+}
+
+/// Flatten restaurant lists, keeping only the first of any restaurant listed
+/// in more than one list.
+///
+/// Returns an error if two restaurants in the same list have the same
+/// [`RestaurantId`].
+fn dedup(lists: Vec<Vec<Restaurant>>) -> Result<Vec<Restaurant>> {
+    let mut seen = BTreeSet::new();
+
+    lists
+        .iter()
+        .try_for_each(|list| RestaurantId::check_collisions(list))?;
+
+    Ok(lists
+        .into_iter()
+        .flatten()
+        .filter(|restaurant| seen.insert(restaurant.id()))
+        .collect())
+}
@@ -0,0 +1,93 @@
+use reqwest::Url;
+use scraper::ElementRef;
+
+use crate::{Error, Result};
+
+/// The result of leniently parsing a list of elements, where one malformed
+/// element does not prevent the rest from being parsed.
+#[derive(Debug)]
+pub struct ParseReport<T> {
+    /// The items that were parsed successfully, in the order they appeared.
+    pub items: Vec<T>,
+
+    /// The errors that occurred while parsing the other elements.
+    pub errors: Vec<ParseFailure>,
+}
+
+impl<T> ParseReport<T> {
+    /// Parse every element in `elements` with `parse`, recording an error for
+    /// each one that fails to parse.
+    ///
+    /// `name` is used to read the name of an element that failed to parse, if
+    /// possible.
+    pub(crate) fn from_elements<'a>(
+        elements: impl IntoIterator<Item = ElementRef<'a>>,
+        parse: impl Fn(ElementRef<'a>) -> Result<T>,
+        name: impl Fn(ElementRef<'a>) -> Option<String>,
+    ) -> Self {
+        let mut items = Vec::new();
+        let mut errors = Vec::new();
+
+        for (index, element) in elements.into_iter().enumerate() {
+            match parse(element) {
+                Ok(item) => items.push(item),
+                Err(error) => errors.push(ParseFailure {
+                    index,
+                    name: name(element),
+                    error: error.with_element(element),
+                }),
+            }
+        }
+
+        Self { items, errors }
+    }
+
+    /// Record the url of the page that was parsed in every error.
+    #[must_use]
+    pub(crate) fn with_url(self, url: &Url) -> Self {
+        Self {
+            items: self.items,
+            errors: self
+                .errors
+                .into_iter()
+                .map(|failure| ParseFailure {
+                    error: failure.error.with_url(url),
+                    ..failure
+                })
+                .collect(),
+        }
+    }
+
+    /// Whether every element was parsed successfully.
+    #[must_use]
+    pub const fn is_complete(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Convert this [`ParseReport`] into a [`Result`], failing with the first
+    /// error if any element failed to parse.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if any element failed to parse.
+    pub fn into_result(self) -> Result<Vec<T>> {
+        match self.errors.into_iter().next() {
+            Some(failure) => Err(failure.error),
+            None => Ok(self.items),
+        }
+    }
+}
+
+/// An element that failed to parse in a [`ParseReport`].
+#[derive(Debug)]
+pub struct ParseFailure {
+    /// The index of the element in the list, counting elements that were
+    /// parsed successfully.
+    pub index: usize,
+
+    /// The name of the item the element describes, if it could be read.
+    pub name: Option<String>,
+
+    /// The error that occurred while parsing the element.
+    pub error: Error,
+}
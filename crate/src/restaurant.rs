@@ -3,13 +3,50 @@ use std::{
     fmt::{self, Display, Formatter},
 };
 
-use chrono::{Duration, Local, NaiveDate};
-use futures::{stream, StreamExt, TryStreamExt};
+use chrono::{DateTime, Duration, NaiveDate, TimeZone};
+use chrono_tz::Tz;
+use futures::Stream;
+use once_cell::sync::Lazy;
 use reqwest::Url;
 use scraper::{ElementRef, Html};
 use serde::{Deserialize, Serialize};
 
-use crate::{selector, Error, FoodType, Location, Result, Times, CLIENT};
+use crate::{
+    ical,
+    schedule::{self, Hours},
+    search::{self, SearchResult},
+    selector, CoffeeBrand, Error, FoodType, Location, OpenNowReconciliation, OpenStatus,
+    ParseReport, RestaurantId, Result, Times, WeeklySummary, DEFAULT_CLIENT, TIMEZONE,
+};
+
+macro_rules! select_text {
+    ($element:expr, $selector:literal, $name:literal) => {
+        $element
+            .select(selector!($selector))
+            .next()
+            .ok_or_else(|| Error::element_not_found($name, $selector).with_element($element))?
+            .text()
+            .next()
+            .ok_or_else(|| Error::text_not_found($name, $selector).with_element($element))?
+            .trim()
+    };
+}
+
+macro_rules! select_optional_text {
+    ($element:expr, $selector:literal, $name:literal) => {
+        $element
+            .select(selector!($selector))
+            .next()
+            .map(|element| {
+                element
+                    .text()
+                    .next()
+                    .ok_or_else(|| Error::text_not_found($name, $selector).with_element(element))
+                    .map(|s| s.trim())
+            })
+            .transpose()?
+    };
+}
 
 /// A restaurant that serves food.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
@@ -18,6 +55,11 @@ pub struct Restaurant {
     pub name: String,
 
     /// The restaurant's location.
+    ///
+    /// If the restaurant was parsed from a restaurant list, the location's slug
+    /// is guessed from its name. See [`LocationResolver`].
+    ///
+    /// [`LocationResolver`]: crate::LocationResolver
     pub location: Location,
 
     /// The restaurant's location data.
@@ -26,11 +68,20 @@ pub struct Restaurant {
     /// The location's phone number.
     pub location_phone: Option<String>,
 
-    /// The restaurant's open status.
+    /// The restaurant's open status, by date on campus (in [`TIMEZONE`]).
     pub schedule: Option<BTreeMap<NaiveDate, Times>>,
 
     /// The restaurant's food type tags.
     pub tags: BTreeSet<FoodType>,
+
+    /// The coffee brands the restaurant serves.
+    ///
+    /// Restaurant cards do not list coffee brands, so this is only filled in
+    /// when the restaurant is fetched along with the coffee brand pages (e.g.
+    /// by [`Restaurant::all_with_coffee_brands`] or
+    /// [`CoffeeBrand::restaurants`]).
+    #[serde(default)]
+    pub coffee_brands: BTreeSet<CoffeeBrand>,
 }
 
 impl Restaurant {
@@ -41,16 +92,36 @@ impl Restaurant {
     /// This function will return an error if sending the request or parsing the
     /// response fails.
     pub async fn all() -> Result<Vec<Self>> {
-        stream::iter(Location::all().await?)
-            .then(|location| async move {
-                location
-                    .restaurants()
-                    .await
-                    .map(|v| stream::iter(v).map(Ok))
-            })
-            .try_flatten()
-            .try_collect()
-            .await
+        DEFAULT_CLIENT.restaurants().await
+    }
+
+    /// Get every restaurant on MacEats, with the coffee brands each restaurant
+    /// serves filled in.
+    ///
+    /// See [`MacEatsClient::restaurants_with_coffee_brands`].
+    ///
+    /// [`MacEatsClient::restaurants_with_coffee_brands`]: crate::MacEatsClient::restaurants_with_coffee_brands
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if sending a request or parsing a
+    /// response fails.
+    pub async fn all_with_coffee_brands() -> Result<Vec<Self>> {
+        DEFAULT_CLIENT.restaurants_with_coffee_brands().await
+    }
+
+    /// Get a [`Stream`] of every restaurant on MacEats, yielding the
+    /// restaurants at each [`Location`] as soon as its page has been parsed.
+    ///
+    /// Locations are yielded in the order their pages finish, not the order
+    /// they are listed in. Use [`Restaurant::all`] for the listed order.
+    ///
+    /// # Errors
+    ///
+    /// The stream will yield an error if sending a request or parsing a
+    /// response fails.
+    pub fn stream_all() -> impl Stream<Item = Result<Self>> {
+        Lazy::force(&DEFAULT_CLIENT).restaurants_stream()
     }
 
     /// Get all restaurants open now.
@@ -60,12 +131,38 @@ impl Restaurant {
     /// This function will return an error if sending the request or parsing the
     /// response fails.
     pub async fn open_now() -> Result<Vec<Self>> {
-        Self::from_restaurant_list_url(
-            "https://maceats.mcmaster.ca/open-now"
-                .parse()
-                .expect("static url is valid"),
-        )
-        .await
+        DEFAULT_CLIENT.open_now().await
+    }
+
+    /// Compare which of `restaurants` are open now according to their
+    /// schedules with which are listed on MacEats' open now page.
+    ///
+    /// See [`MacEatsClient::reconcile_open_now`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if sending the request or parsing the
+    /// response fails.
+    ///
+    /// [`MacEatsClient::reconcile_open_now`]: crate::MacEatsClient::reconcile_open_now
+    pub async fn reconcile_open_now(restaurants: &[Self]) -> Result<OpenNowReconciliation> {
+        DEFAULT_CLIENT.reconcile_open_now(restaurants).await
+    }
+
+    /// Get the restaurants in `restaurants` that are open at `instant`,
+    /// according to their schedules.
+    ///
+    /// Unlike [`Restaurant::open_now`], this does not send any requests, so it
+    /// can be used with restaurants that were fetched earlier.
+    #[must_use]
+    pub fn open_at<'a, T: TimeZone>(
+        restaurants: &'a [Self],
+        instant: &DateTime<T>,
+    ) -> Vec<&'a Self> {
+        restaurants
+            .iter()
+            .filter(|restaurant| restaurant.is_open_at(instant))
+            .collect()
     }
 
     /// Parse a restaurant list into a [`Vec<Restaurant>`].
@@ -75,24 +172,262 @@ impl Restaurant {
     /// This function will return an error if sending the request or parsing the
     /// response fails.
     pub async fn from_restaurant_list_url(url: Url) -> Result<Vec<Self>> {
-        let response = CLIENT.get(url).send().await?.error_for_status()?;
-        let html = Html::parse_document(&response.text().await?);
+        DEFAULT_CLIENT.restaurant_list(url).await
+    }
+
+    /// Get this restaurant's [`RestaurantId`], which stays the same between
+    /// scrapes.
+    #[must_use]
+    pub fn id(&self) -> RestaurantId {
+        RestaurantId::new(&self.location.slug, &self.name)
+    }
+
+    /// Get the restaurant on MacEats with the id `id`, if there is one.
+    ///
+    /// See [`MacEatsClient::restaurant`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if sending a request or parsing a
+    /// response fails, or if more than one restaurant has the id `id`.
+    ///
+    /// [`MacEatsClient::restaurant`]: crate::MacEatsClient::restaurant
+    pub async fn by_id(id: &RestaurantId) -> Result<Option<Self>> {
+        DEFAULT_CLIENT.restaurant(id).await
+    }
+
+    /// Get the restaurant in `restaurants` with the id `id`, if there is one.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if more than one restaurant in
+    /// `restaurants` has the id `id`.
+    pub fn find_by_id<'a>(restaurants: &'a [Self], id: &RestaurantId) -> Result<Option<&'a Self>> {
+        let mut found = restaurants
+            .iter()
+            .filter(|restaurant| restaurant.id() == *id);
+
+        match (found.next(), found.next()) {
+            (Some(first), Some(second)) => Err(Error::RestaurantIdCollision {
+                id: id.clone(),
+                names: (first.name.clone(), second.name.clone()),
+            }),
+            (first, _) => Ok(first),
+        }
+    }
+
+    /// Get whether this restaurant is open at `instant`, according to its
+    /// schedule.
+    ///
+    /// The restaurant is considered to be opening or closing soon within
+    /// [`OpenStatus::SOON`] of it opening or closing.
+    #[must_use]
+    pub fn status_at<T: TimeZone>(&self, instant: &DateTime<T>) -> OpenStatus {
+        self.status_at_with_soon(instant, OpenStatus::SOON)
+    }
+
+    /// Get whether this restaurant is open at `instant`, according to its
+    /// schedule, where the restaurant is considered to be opening or closing
+    /// soon within `soon` of it opening or closing.
+    #[must_use]
+    pub fn status_at_with_soon<T: TimeZone>(
+        &self,
+        instant: &DateTime<T>,
+        soon: Duration,
+    ) -> OpenStatus {
+        let instant = instant.with_timezone(&TIMEZONE);
+
+        let Some(hours) = self.hours() else {
+            return OpenStatus::Unknown;
+        };
+
+        if let Some((_, closes_at)) = hours.range_at(instant) {
+            if closes_at - instant <= soon && !hours.may_stay_open(closes_at) {
+                OpenStatus::ClosingSoon { closes_at }
+            } else {
+                OpenStatus::Open { closes_at }
+            }
+        } else if !hours.covers(instant) {
+            OpenStatus::Unknown
+        } else {
+            match hours.range_after(instant) {
+                Some((opens_at, _)) if opens_at - instant <= soon => {
+                    OpenStatus::OpeningSoon { opens_at }
+                }
+                range => OpenStatus::Closed {
+                    opens_at: range.map(|(opens_at, _)| opens_at),
+                },
+            }
+        }
+    }
+
+    /// Whether this restaurant is open at `instant`, according to its
+    /// schedule.
+    ///
+    /// This is [`false`] if the restaurant's status at `instant` is unknown.
+    #[must_use]
+    pub fn is_open_at<T: TimeZone>(&self, instant: &DateTime<T>) -> bool {
+        self.status_at(instant).is_open()
+    }
+
+    /// Get when this restaurant next opens after `after`, according to its
+    /// schedule.
+    ///
+    /// This returns [`None`] if the restaurant does not open again before the
+    /// end of its schedule.
+    #[must_use]
+    pub fn next_opening<T: TimeZone>(&self, after: &DateTime<T>) -> Option<DateTime<Tz>> {
+        let after = after.with_timezone(&TIMEZONE);
+
+        self.hours()?.range_after(after).map(|(from, _)| from)
+    }
 
-        Self::from_restaurant_list_html(&html)
+    /// Get when this restaurant next closes after `after`, according to its
+    /// schedule, which may be the end of an overnight range on the day after
+    /// the schedule ends.
+    ///
+    /// This returns [`None`] if the restaurant does not close before the end of
+    /// its schedule, including if it is open all day on the last date the
+    /// schedule covers.
+    #[must_use]
+    pub fn next_closing<T: TimeZone>(&self, after: &DateTime<T>) -> Option<DateTime<Tz>> {
+        let after = after.with_timezone(&TIMEZONE);
+        let hours = self.hours()?;
+
+        hours
+            .range_at(after)
+            .or_else(|| hours.range_after(after))
+            .map(|(_, to)| to)
+            .filter(|&to| !hours.may_stay_open(to))
+    }
+
+    /// Search `restaurants` for `query`, matching their names, locations and
+    /// location details, and allowing for typos and building codes (e.g.
+    /// `MUSC`).
+    ///
+    /// Results are ordered from best to worst match, and poor matches are left
+    /// out.
+    #[must_use]
+    pub fn search<'a>(restaurants: &'a [Self], query: &str) -> Vec<SearchResult<'a, Self>> {
+        search::search(restaurants, query, |restaurant| {
+            let mut fields = vec![
+                (restaurant.name.as_str(), 1.0),
+                (restaurant.location.name.as_str(), 0.9),
+            ];
+
+            if let Some(details) = &restaurant.location_details {
+                fields.push((details, 0.8));
+            }
+
+            fields
+        })
+    }
+
+    /// Get an RFC 5545 iCalendar document with an event for every range this
+    /// restaurant is open on each date in its schedule.
+    ///
+    /// Each event has a UID made from the restaurant's [`RestaurantId`], the
+    /// date and the range's position in that date's times, so it stays the
+    /// same between exports even if the range's times change. Calendar apps
+    /// subscribed to the document then update events instead of duplicating
+    /// them.
+    #[must_use]
+    pub fn to_icalendar(&self) -> String {
+        ical::write([self])
+    }
+
+    /// Get an RFC 5545 iCalendar document with an event for every range each
+    /// of `restaurants` is open on each date in its schedule.
+    ///
+    /// See [`Restaurant::to_icalendar`].
+    #[must_use]
+    pub fn icalendar<'a>(restaurants: impl IntoIterator<Item = &'a Self>) -> String {
+        ical::write(restaurants)
+    }
+
+    /// Get a compact summary of this restaurant's schedule, if it has one.
+    #[must_use]
+    pub fn weekly_summary(&self) -> Option<WeeklySummary> {
+        self.schedule.as_ref().map(WeeklySummary::new)
+    }
+
+    /// Get the instants this restaurant is open, according to its schedule.
+    fn hours(&self) -> Option<Hours> {
+        self.schedule.as_ref().and_then(Hours::new)
     }
 
     /// Parse a restaurant list [`Html`] document into a [`Vec<Restaurant>`].
     ///
+    /// Schedules are dated relative to today on campus. Use
+    /// [`Restaurant::from_restaurant_list_html_as_of`] to parse a page fetched
+    /// on another day.
+    ///
     /// # Errors
     ///
     /// This function will return an error if parsing the response fails.
     ///
     /// [`Html`]: scraper::Html
     pub fn from_restaurant_list_html(html: &Html) -> Result<Vec<Self>> {
+        Self::from_restaurant_list_html_as_of(html, schedule::today())
+    }
+
+    /// Parse a restaurant list [`Html`] document fetched on the date `today` on
+    /// campus into a [`Vec<Restaurant>`].
+    ///
+    /// Schedule rows labelled with relative days (e.g. `Today`) are dated
+    /// relative to `today`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if parsing the response fails.
+    ///
+    /// [`Html`]: scraper::Html
+    pub fn from_restaurant_list_html_as_of(html: &Html, today: NaiveDate) -> Result<Vec<Self>> {
         html.select(selector!("div.unit"))
-            .map(TryInto::try_into)
+            .map(|element| Self::from_element(element, today))
             .collect()
     }
+
+    /// Leniently parse a restaurant list [`Html`] document into a
+    /// [`ParseReport<Restaurant>`], skipping restaurants that fail to parse
+    /// instead of failing the whole list.
+    ///
+    /// Schedules are dated relative to today on campus.
+    ///
+    /// [`Html`]: scraper::Html
+    #[must_use]
+    pub fn from_restaurant_list_html_lenient(html: &Html) -> ParseReport<Self> {
+        Self::from_restaurant_list_html_lenient_as_of(html, schedule::today())
+    }
+
+    /// Leniently parse a restaurant list [`Html`] document fetched on the date
+    /// `today` on campus into a [`ParseReport<Restaurant>`].
+    ///
+    /// See [`Restaurant::from_restaurant_list_html_lenient`] and
+    /// [`Restaurant::from_restaurant_list_html_as_of`].
+    ///
+    /// [`Html`]: scraper::Html
+    #[must_use]
+    pub fn from_restaurant_list_html_lenient_as_of(
+        html: &Html,
+        today: NaiveDate,
+    ) -> ParseReport<Self> {
+        ParseReport::from_elements(
+            html.select(selector!("div.unit")),
+            |element| Self::from_element(element, today),
+            |element| {
+                Some(
+                    element
+                        .select(selector!("h1.title"))
+                        .next()?
+                        .text()
+                        .next()?
+                        .trim()
+                        .to_owned(),
+                )
+            },
+        )
+    }
 }
 
 impl Display for Restaurant {
@@ -105,58 +440,33 @@ impl TryFrom<ElementRef<'_>> for Restaurant {
     type Error = Error;
 
     fn try_from(element: ElementRef<'_>) -> Result<Self> {
-        macro_rules! select_text {
-            ($selector:literal, $name:literal) => {
-                element
-                    .select(selector!($selector))
-                    .next()
-                    .ok_or(Error::ElementNotFound($name))?
-                    .text()
-                    .next()
-                    .ok_or(Error::TextNotFound($name))?
-                    .trim()
-            };
-        }
+        Self::from_element(element, schedule::today())
+    }
+}
 
-        macro_rules! select_optional_text {
-            ($selector:literal, $name:literal) => {
-                element
-                    .select(selector!($selector))
-                    .next()
-                    .map(|element| {
-                        element
-                            .text()
-                            .next()
-                            .ok_or(Error::TextNotFound($name))
-                            .map(|s| s.trim())
-                    })
-                    .transpose()?
-            };
-        }
+impl Restaurant {
+    /// Parse a restaurant card, dating its schedule relative to `today`.
+    fn from_element(element: ElementRef<'_>, today: NaiveDate) -> Result<Self> {
+        let name = select_text!(element, "h1.title", "name").to_owned();
 
-        let name = select_text!("h1.title", "name").to_owned();
+        Self::from_card(element, name.clone(), today).map_err(|e| e.with_subject(&name))
+    }
 
-        let location = Location::new(select_text!("h2.location", "location"));
+    /// Parse the rest of a restaurant card, once its name has been parsed.
+    fn from_card(element: ElementRef<'_>, name: String, today: NaiveDate) -> Result<Self> {
+        let location = Location::new(select_text!(element, "h2.location", "location"));
 
         let location_details =
-            select_optional_text!("div.location-data", "location details").map(ToOwned::to_owned);
+            select_optional_text!(element, "div.location-data", "location details")
+                .map(ToOwned::to_owned);
 
-        let location_phone =
-            select_optional_text!("div.location-phone", "location phone").map(ToOwned::to_owned);
+        let location_phone = select_optional_text!(element, "div.location-phone", "location phone")
+            .map(ToOwned::to_owned);
 
         let schedule = element
             .select(selector!("div.schedule"))
             .next()
-            .map(|schedule| {
-                let today = Local::today().naive_local();
-
-                let times = schedule.select(selector!("td.time")).map(TryInto::try_into);
-
-                (0..7)
-                    .zip(times)
-                    .map(|(i, times)| times.map(|times| (today + Duration::days(i), times)))
-                    .collect()
-            })
+            .map(|element| schedule::parse(element, today))
             .transpose()?;
 
         let tags = element
@@ -177,6 +487,47 @@ impl TryFrom<ElementRef<'_>> for Restaurant {
             location_phone,
             schedule,
             tags,
+            coffee_brands: BTreeSet::new(),
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::{at, scheduled_restaurant};
+
+    #[test]
+    fn next_closing_crosses_into_day_after_schedule() {
+        let restaurant = scheduled_restaurant(&[(20, "7:30 am - 9 pm"), (21, "10 pm - 2 am")]);
+
+        assert_eq!(restaurant.next_closing(&at(21, 12, 0)), Some(at(22, 2, 0)));
+        assert_eq!(restaurant.next_closing(&at(21, 23, 0)), Some(at(22, 2, 0)));
+        assert_eq!(restaurant.next_closing(&at(22, 1, 0)), Some(at(22, 2, 0)));
+    }
+
+    #[test]
+    fn next_closing_before_schedule() {
+        let restaurant = scheduled_restaurant(&[(20, "7:30 am - 9 pm"), (21, "10 pm - 2 am")]);
+
+        assert_eq!(restaurant.next_closing(&at(19, 12, 0)), Some(at(20, 21, 0)));
+        assert_eq!(restaurant.next_opening(&at(19, 12, 0)), Some(at(20, 7, 30)));
+    }
+
+    #[test]
+    fn no_next_closing_after_last_opening() {
+        let restaurant = scheduled_restaurant(&[(20, "7:30 am - 9 pm"), (21, "Closed")]);
+
+        assert_eq!(restaurant.next_closing(&at(20, 22, 0)), None);
+        assert_eq!(restaurant.next_opening(&at(20, 22, 0)), None);
+        assert_eq!(scheduled_restaurant(&[]).next_closing(&at(20, 12, 0)), None);
+    }
+
+    #[test]
+    fn next_closing_at_midnight_on_last_date() {
+        let closes = scheduled_restaurant(&[(20, "7:30 am - 9 pm"), (21, "8 pm - midnight")]);
+        let open_all_day = scheduled_restaurant(&[(20, "7:30 am - 9 pm"), (21, "Open 24 hours")]);
+
+        assert_eq!(closes.next_closing(&at(21, 23, 45)), Some(at(22, 0, 0)));
+        assert_eq!(open_all_day.next_closing(&at(21, 12, 0)), None);
+    }
+}
@@ -0,0 +1,163 @@
+use std::time::{Duration, SystemTime};
+
+use rand::Rng;
+use reqwest::{
+    header::{HeaderMap, RETRY_AFTER},
+    StatusCode,
+};
+
+/// How a [`MacEatsClient`] retries requests that fail for transient reasons.
+///
+/// Requests are retried on connection errors, timeouts, `5xx` responses and
+/// `429 Too Many Requests` responses with a `Retry-After` header. Any other
+/// failure (e.g. a `404 Not Found` response, a `429 Too Many Requests` response
+/// without a `Retry-After` header, or a parse error) is returned immediately.
+///
+/// Between attempts, the client waits for an exponentially increasing
+/// backoff, optionally with jitter. After a `429 Too Many Requests` response,
+/// the delay from its `Retry-After` header is used instead, unless it is
+/// longer than the maximum backoff, in which case the request is not retried.
+///
+/// [`MacEatsClient`]: crate::MacEatsClient
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    jitter: bool,
+}
+
+impl RetryPolicy {
+    /// Create a new [`RetryPolicy`] that makes at most `max_attempts` attempts
+    /// per request, using the default backoff.
+    #[must_use]
+    pub const fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(10),
+            jitter: true,
+        }
+    }
+
+    /// Create a new [`RetryPolicy`] that never retries.
+    #[must_use]
+    pub const fn none() -> Self {
+        Self::new(1)
+    }
+
+    /// Set the backoff before the first retry, and the maximum backoff between
+    /// any two attempts.
+    #[must_use]
+    pub const fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Set whether the backoff is randomized, to avoid many clients retrying
+    /// in lockstep.
+    #[must_use]
+    pub const fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Get the maximum number of attempts made per request.
+    #[must_use]
+    pub const fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Get the maximum backoff between any two attempts.
+    #[must_use]
+    pub const fn max_backoff(&self) -> Duration {
+        self.max_backoff
+    }
+
+    /// Get the delay before making attempt `attempt + 1`, where `attempt`
+    /// starts at `1`.
+    #[must_use]
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(2_u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(self.max_backoff);
+
+        if self.jitter {
+            let half = backoff / 2;
+
+            half + rand::thread_rng().gen_range(Duration::ZERO..=half)
+        } else {
+            backoff
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new(3)
+    }
+}
+
+/// Whether a [`reqwest::Error`] from sending a request could succeed if the
+/// request was sent again, where `retry_after` is the delay requested by the
+/// response's `Retry-After` header, if any.
+pub fn is_transient(error: &reqwest::Error, retry_after: Option<Duration>) -> bool {
+    error.is_connect()
+        || error.is_timeout()
+        || error
+            .status()
+            .is_some_and(|status| is_transient_status(status, retry_after))
+}
+
+/// Whether a response with this [`StatusCode`] could succeed if the request
+/// was sent again.
+///
+/// A `429 Too Many Requests` response is only retried if it says when to retry
+/// with a `Retry-After` header.
+fn is_transient_status(status: StatusCode, retry_after: Option<Duration>) -> bool {
+    status.is_server_error() || (status == StatusCode::TOO_MANY_REQUESTS && retry_after.is_some())
+}
+
+/// Get the delay requested by a `Retry-After` header, in either its
+/// delay-seconds or HTTP-date form.
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    value.parse().map(Duration::from_secs).ok().or_else(|| {
+        httpdate::parse_http_date(value)
+            .ok()?
+            .duration_since(SystemTime::now())
+            .ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn retries_too_many_requests_only_with_retry_after() {
+        let delay = Some(Duration::from_secs(1));
+
+        assert!(is_transient_status(StatusCode::SERVICE_UNAVAILABLE, None));
+        assert!(is_transient_status(StatusCode::TOO_MANY_REQUESTS, delay));
+        assert!(!is_transient_status(StatusCode::TOO_MANY_REQUESTS, None));
+        assert!(!is_transient_status(StatusCode::NOT_FOUND, delay));
+    }
+
+    #[test]
+    fn reads_retry_after_seconds() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static(" 3 "));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(3)));
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("soon"));
+        assert_eq!(retry_after(&headers), None);
+    }
+}
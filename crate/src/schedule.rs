@@ -0,0 +1,561 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use scraper::ElementRef;
+
+use crate::{selector, Error, Open, Result, Times, TIMEZONE};
+
+/// Get today's date on campus.
+pub fn today() -> NaiveDate {
+    Utc::now().with_timezone(&TIMEZONE).date_naive()
+}
+
+/// Get the instant a time on a date on campus refers to.
+///
+/// A time skipped by a DST transition is taken to be an hour later, and a time
+/// repeated by one is taken to be its first occurrence.
+pub fn instant(date: NaiveDate, time: NaiveTime) -> DateTime<Tz> {
+    let local = date.and_time(time);
+
+    TIMEZONE
+        .from_local_datetime(&local)
+        .earliest()
+        .or_else(|| {
+            TIMEZONE
+                .from_local_datetime(&(local + Duration::hours(1)))
+                .earliest()
+        })
+        .expect("campus times should only skip an hour")
+}
+
+/// The instants a restaurant is open, as given by its schedule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hours {
+    /// The first instant covered by the schedule.
+    pub start: DateTime<Tz>,
+
+    /// The instant after the last date covered by the schedule.
+    pub end: DateTime<Tz>,
+
+    /// The ranges of instants the restaurant is open, in order, with
+    /// overlapping and adjacent ranges merged.
+    pub ranges: Vec<(DateTime<Tz>, DateTime<Tz>)>,
+
+    /// Whether the restaurant is open all day on the last date covered by the
+    /// schedule, so may stay open past [`Hours::end`].
+    pub open_at_end: bool,
+}
+
+impl Hours {
+    /// Get the [`Hours`] of a schedule, or [`None`] if it is empty.
+    pub fn new(schedule: &BTreeMap<NaiveDate, Times>) -> Option<Self> {
+        let (&first, _) = schedule.first_key_value()?;
+        let (&last, last_times) = schedule.last_key_value()?;
+
+        let mut ranges = schedule
+            .iter()
+            .filter_map(|(&date, times)| match times {
+                Times::Open(opens) => Some(
+                    opens
+                        .iter()
+                        .map(move |open| (open.start_on(date), open.end_on(date))),
+                ),
+                Times::Closed => None,
+            })
+            .flatten()
+            .collect::<Vec<_>>();
+
+        ranges.sort_unstable();
+
+        let ranges = ranges
+            .into_iter()
+            .fold(Vec::new(), |mut merged, (from, to)| {
+                match merged.last_mut() {
+                    Some((_, last_to)) if from <= *last_to => *last_to = to.max(*last_to),
+                    _ => merged.push((from, to)),
+                }
+
+                merged
+            });
+
+        Some(Self {
+            start: instant(first, NaiveTime::MIN),
+            end: instant(last + Duration::days(1), NaiveTime::MIN),
+            ranges,
+            open_at_end: matches!(last_times, Times::Open(opens) if opens.iter().any(Open::is_all_day)),
+        })
+    }
+
+    /// Whether `instant` is covered by the schedule.
+    pub fn covers(&self, instant: DateTime<Tz>) -> bool {
+        (self.start..self.end).contains(&instant)
+    }
+
+    /// Whether the restaurant may stay open past `to`, the end of one of its
+    /// ranges, because the schedule ends while the restaurant is open all day.
+    ///
+    /// A range that ends at [`Hours::end`] for any other reason (e.g. `8 pm -
+    /// midnight` on the last date) really closes then.
+    pub fn may_stay_open(&self, to: DateTime<Tz>) -> bool {
+        self.open_at_end && to == self.end
+    }
+
+    /// Get the range the restaurant is open during at `instant`, if any.
+    pub fn range_at(&self, instant: DateTime<Tz>) -> Option<(DateTime<Tz>, DateTime<Tz>)> {
+        self.ranges
+            .iter()
+            .copied()
+            .find(|&(from, to)| (from..to).contains(&instant))
+    }
+
+    /// Get the first range the restaurant opens during after `instant`, if
+    /// any.
+    pub fn range_after(&self, instant: DateTime<Tz>) -> Option<(DateTime<Tz>, DateTime<Tz>)> {
+        self.ranges
+            .iter()
+            .copied()
+            .find(|&(from, _)| from > instant)
+    }
+}
+
+/// Parse a `div.schedule` element into the [`Times`] for each date it shows.
+///
+/// Each row of the schedule is dated using its day/date label (e.g. `Today`,
+/// `Thu` or `Oct 20`), relative to `today`. The rows must be consecutive days.
+pub fn parse(schedule: ElementRef<'_>, today: NaiveDate) -> Result<BTreeMap<NaiveDate, Times>> {
+    let mut first = None;
+
+    schedule
+        .select(selector!("tr"))
+        .filter(|row| row.select(selector!("td.time")).next().is_some())
+        .zip(0..)
+        .map(|(row, i)| {
+            let text = row
+                .select(selector!("th, td:not(.time)"))
+                .flat_map(|cell| cell.text())
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            let label = DayLabel::parse(&text)
+                .ok_or_else(|| Error::schedule_label(text.trim()).with_element(row))?;
+
+            let first = match first {
+                Some(first) => first,
+                None => *first.insert(
+                    label
+                        .anchor(today)
+                        .ok_or_else(|| Error::schedule_label(text.trim()).with_element(row))?,
+                ),
+            };
+
+            let date = first + Duration::days(i);
+
+            if !label.matches(date, today) {
+                return Err(Error::schedule_mismatch(text.trim(), date).with_element(row));
+            }
+
+            let times = row
+                .select(selector!("td.time"))
+                .next()
+                .expect("row should have a time")
+                .try_into()?;
+
+            Ok((date, times))
+        })
+        .collect()
+}
+
+/// A day/date label from a row of a schedule.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct DayLabel {
+    /// The number of days after today, for `Today` and `Tomorrow`.
+    offset: Option<i64>,
+
+    /// The day of the week.
+    weekday: Option<Weekday>,
+
+    /// The month (starting at 1) and day of the month.
+    month_day: Option<(u32, u32)>,
+
+    /// The year.
+    year: Option<i32>,
+}
+
+impl DayLabel {
+    const WEEKDAYS: [(&'static str, Weekday); 7] = [
+        ("monday", Weekday::Mon),
+        ("tuesday", Weekday::Tue),
+        ("wednesday", Weekday::Wed),
+        ("thursday", Weekday::Thu),
+        ("friday", Weekday::Fri),
+        ("saturday", Weekday::Sat),
+        ("sunday", Weekday::Sun),
+    ];
+
+    const MONTHS: [(&'static str, u32); 12] = [
+        ("january", 1),
+        ("february", 2),
+        ("march", 3),
+        ("april", 4),
+        ("may", 5),
+        ("june", 6),
+        ("july", 7),
+        ("august", 8),
+        ("september", 9),
+        ("october", 10),
+        ("november", 11),
+        ("december", 12),
+    ];
+
+    /// Parse a label such as `Today`, `Thu`, `Thursday, October 20th` or
+    /// `2022-10-20`, returning [`None`] if it contains no recognisable day.
+    ///
+    /// Words that are not part of a date (e.g. `Holiday`) are ignored.
+    fn parse(text: &str) -> Option<Self> {
+        let mut label = Self::default();
+        let mut month = None;
+        let mut numbers = Vec::new();
+
+        for token in text
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|token| !token.is_empty())
+        {
+            let token = token.to_ascii_lowercase();
+            let digits = ["st", "nd", "rd", "th"]
+                .into_iter()
+                .find_map(|suffix| token.strip_suffix(suffix))
+                .filter(|digits| !digits.is_empty() && digits.len() <= 2)
+                .unwrap_or(&token);
+
+            if let Ok(number) = digits.parse::<u32>() {
+                numbers.push((number, digits.len()));
+            } else if token == "today" {
+                label.offset = Some(0);
+            } else if token == "tomorrow" {
+                label.offset = Some(1);
+            } else if let Some(weekday) = Self::prefix_of(&token, &Self::WEEKDAYS) {
+                label.weekday = Some(weekday);
+            } else if let Some(m) = Self::prefix_of(&token, &Self::MONTHS) {
+                month = Some(m);
+            } else if token.bytes().any(|b| b.is_ascii_digit()) {
+                return None;
+            }
+        }
+
+        let year = |(n, len): (u32, usize)| (len == 4).then(|| i32::try_from(n).ok()).flatten();
+
+        match (month, numbers.as_slice()) {
+            (None, []) => {}
+            (Some(month), &[day]) => label.month_day = Some((month, day.0)),
+            (Some(month), &[day, y] | &[y, day]) if year(y).is_some() => {
+                label.month_day = Some((month, day.0));
+                label.year = year(y);
+            }
+            (None, &[y, month, day]) if year(y).is_some() => {
+                label.month_day = Some((month.0, day.0));
+                label.year = year(y);
+            }
+            _ => return None,
+        }
+
+        (label != Self::default()).then_some(label)
+    }
+
+    /// Find the value in `table` whose name starts with `token`, if `token`
+    /// is at least 3 characters long.
+    fn prefix_of<T: Copy>(token: &str, table: &[(&str, T)]) -> Option<T> {
+        (token.len() >= 3)
+            .then(|| {
+                table
+                    .iter()
+                    .find(|(name, _)| name.starts_with(token))
+                    .map(|&(_, value)| value)
+            })
+            .flatten()
+    }
+
+    /// Get the date this label most likely refers to.
+    fn anchor(&self, today: NaiveDate) -> Option<NaiveDate> {
+        if let Some((month, day)) = self.month_day {
+            return self.year.map_or_else(
+                || {
+                    [today.year() - 1, today.year(), today.year() + 1]
+                        .into_iter()
+                        .filter_map(|year| NaiveDate::from_ymd_opt(year, month, day))
+                        .min_by_key(|date| (*date - today).num_days().abs())
+                },
+                |year| NaiveDate::from_ymd_opt(year, month, day),
+            );
+        }
+
+        if let Some(offset) = self.offset {
+            return Some(today + Duration::days(offset));
+        }
+
+        self.weekday.map(|weekday| {
+            let offset = i64::from(weekday.num_days_from_monday())
+                - i64::from(today.weekday().num_days_from_monday());
+
+            today + Duration::days((offset + 3).rem_euclid(7) - 3)
+        })
+    }
+
+    /// Whether every part of this label agrees with `date`.
+    fn matches(&self, date: NaiveDate, today: NaiveDate) -> bool {
+        self.offset
+            .is_none_or(|offset| today + Duration::days(offset) == date)
+            && self.weekday.is_none_or(|weekday| date.weekday() == weekday)
+            && self
+                .month_day
+                .is_none_or(|(month, day)| (date.month(), date.day()) == (month, day))
+            && self.year.is_none_or(|year| date.year() == year)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_util::{at, schedule, scheduled_restaurant, ymd},
+        OpenStatus,
+    };
+
+    fn label(text: &str) -> DayLabel {
+        DayLabel::parse(text).unwrap_or_else(|| panic!("{text:?} should parse"))
+    }
+
+    #[test]
+    fn parses_relative_days() {
+        assert_eq!(label("Today").offset, Some(0));
+        assert_eq!(label("tomorrow").offset, Some(1));
+    }
+
+    #[test]
+    fn parses_weekdays() {
+        assert_eq!(label("Thu").weekday, Some(Weekday::Thu));
+        assert_eq!(label("Thursday").weekday, Some(Weekday::Thu));
+        assert_eq!(label("Thurs").weekday, Some(Weekday::Thu));
+    }
+
+    #[test]
+    fn parses_month_days() {
+        let thursday = label("Thursday, October 20");
+        assert_eq!(thursday.weekday, Some(Weekday::Thu));
+        assert_eq!(thursday.month_day, Some((10, 20)));
+        assert_eq!(thursday.year, None);
+
+        assert_eq!(label("Oct 20").month_day, Some((10, 20)));
+        assert_eq!(label("20 Oct").month_day, Some((10, 20)));
+    }
+
+    #[test]
+    fn parses_ordinal_days() {
+        assert_eq!(label("October 20th").month_day, Some((10, 20)));
+        assert_eq!(label("Nov 1st").month_day, Some((11, 1)));
+        assert_eq!(label("Nov 2nd").month_day, Some((11, 2)));
+        assert_eq!(label("Nov 3rd").month_day, Some((11, 3)));
+    }
+
+    #[test]
+    fn parses_years() {
+        let full = label("October 20, 2022");
+        assert_eq!(full.month_day, Some((10, 20)));
+        assert_eq!(full.year, Some(2022));
+
+        let iso = label("2022-10-20");
+        assert_eq!(iso.month_day, Some((10, 20)));
+        assert_eq!(iso.year, Some(2022));
+    }
+
+    #[test]
+    fn ignores_extra_words() {
+        let holiday = label("Today Holiday");
+        assert_eq!(holiday.offset, Some(0));
+
+        assert_eq!(label("Holiday Mon Oct 10th").month_day, Some((10, 10)));
+    }
+
+    #[test]
+    fn rejects_labels_without_a_day() {
+        assert_eq!(DayLabel::parse(""), None);
+        assert_eq!(DayLabel::parse("Holiday"), None);
+        assert_eq!(DayLabel::parse("October"), None);
+        assert_eq!(DayLabel::parse("20"), None);
+        assert_eq!(DayLabel::parse("Oct 20xx"), None);
+    }
+
+    #[test]
+    fn anchors_relative_days() {
+        let today = ymd(2022, 10, 20);
+
+        assert_eq!(label("Today").anchor(today), Some(today));
+        assert_eq!(label("Tomorrow").anchor(today), Some(ymd(2022, 10, 21)));
+    }
+
+    #[test]
+    fn anchors_weekdays_near_today() {
+        // 2022-10-20 is a Thursday.
+        let today = ymd(2022, 10, 20);
+
+        assert_eq!(label("Thu").anchor(today), Some(today));
+        assert_eq!(label("Sat").anchor(today), Some(ymd(2022, 10, 22)));
+        assert_eq!(label("Sun").anchor(today), Some(ymd(2022, 10, 23)));
+        assert_eq!(label("Mon").anchor(today), Some(ymd(2022, 10, 17)));
+    }
+
+    #[test]
+    fn anchors_month_days_across_the_new_year() {
+        let new_years_eve = ymd(2022, 12, 31);
+        assert_eq!(label("Jan 2").anchor(new_years_eve), Some(ymd(2023, 1, 2)));
+
+        let new_years_day = ymd(2023, 1, 1);
+        assert_eq!(
+            label("Dec 30").anchor(new_years_day),
+            Some(ymd(2022, 12, 30))
+        );
+
+        assert_eq!(
+            label("Dec 30, 2023").anchor(new_years_day),
+            Some(ymd(2023, 12, 30))
+        );
+    }
+
+    #[test]
+    fn matches_every_part_of_the_label() {
+        let today = ymd(2022, 12, 31);
+        let tomorrow = ymd(2023, 1, 1);
+
+        assert!(label("Tomorrow").matches(tomorrow, today));
+        assert!(label("Sun Jan 1").matches(tomorrow, today));
+        assert!(label("Sunday, January 1st, 2023").matches(tomorrow, today));
+
+        assert!(!label("Today").matches(tomorrow, today));
+        assert!(!label("Mon Jan 1").matches(tomorrow, today));
+        assert!(!label("Jan 1, 2022").matches(tomorrow, today));
+    }
+
+    #[test]
+    fn matches_weekday_only_labels() {
+        let today = ymd(2022, 10, 20);
+
+        assert!(label("Fri").matches(ymd(2022, 10, 21), today));
+        assert!(label("Fri").matches(ymd(2022, 10, 28), today));
+        assert!(!label("Fri").matches(ymd(2022, 10, 22), today));
+    }
+
+    #[test]
+    fn merges_overnight_ranges() {
+        let hours = Hours::new(&schedule([
+            (20, "10 pm - 2 am"),
+            (21, "1 am - 3 am, 5 pm - 9 pm"),
+        ]))
+        .unwrap();
+
+        assert_eq!(hours.start, at(20, 0, 0));
+        assert_eq!(hours.end, at(22, 0, 0));
+        assert_eq!(
+            hours.ranges,
+            [
+                (at(20, 22, 0), at(21, 3, 0)),
+                (at(21, 17, 0), at(21, 21, 0))
+            ]
+        );
+        assert!(!hours.open_at_end);
+    }
+
+    #[test]
+    fn gets_status() {
+        let restaurant = scheduled_restaurant(&[(20, "7:30 am - 9 pm"), (21, "8 pm - midnight")]);
+
+        assert_eq!(
+            restaurant.status_at(&at(20, 6, 0)),
+            OpenStatus::Closed {
+                opens_at: Some(at(20, 7, 30))
+            }
+        );
+        assert_eq!(
+            restaurant.status_at(&at(20, 7, 15)),
+            OpenStatus::OpeningSoon {
+                opens_at: at(20, 7, 30)
+            }
+        );
+        assert_eq!(
+            restaurant.status_at(&at(20, 12, 0)),
+            OpenStatus::Open {
+                closes_at: at(20, 21, 0)
+            }
+        );
+        assert_eq!(
+            restaurant.status_at(&at(20, 20, 45)),
+            OpenStatus::ClosingSoon {
+                closes_at: at(20, 21, 0)
+            }
+        );
+        assert_eq!(
+            restaurant.status_at(&at(21, 0, 30)),
+            OpenStatus::Closed {
+                opens_at: Some(at(21, 20, 0))
+            }
+        );
+    }
+
+    #[test]
+    fn gets_unknown_status_outside_schedule() {
+        let restaurant = scheduled_restaurant(&[(20, "7:30 am - 9 pm"), (21, "Closed")]);
+
+        assert_eq!(restaurant.status_at(&at(19, 12, 0)), OpenStatus::Unknown);
+        assert_eq!(
+            restaurant.status_at(&at(21, 23, 0)),
+            OpenStatus::Closed { opens_at: None }
+        );
+        assert_eq!(restaurant.status_at(&at(22, 12, 0)), OpenStatus::Unknown);
+        assert_eq!(
+            scheduled_restaurant(&[]).status_at(&at(20, 12, 0)),
+            OpenStatus::Unknown
+        );
+    }
+
+    #[test]
+    fn gets_status_during_overnight_ranges() {
+        let restaurant = scheduled_restaurant(&[(20, "10 pm - 2 am")]);
+
+        assert_eq!(
+            restaurant.status_at(&at(20, 23, 0)),
+            OpenStatus::Open {
+                closes_at: at(21, 2, 0)
+            }
+        );
+        assert_eq!(
+            restaurant.status_at(&at(21, 1, 45)),
+            OpenStatus::ClosingSoon {
+                closes_at: at(21, 2, 0)
+            }
+        );
+        assert_eq!(restaurant.status_at(&at(21, 3, 0)), OpenStatus::Unknown);
+    }
+
+    #[test]
+    fn closes_soon_at_midnight_on_last_date() {
+        let restaurant = scheduled_restaurant(&[(20, "7:30 am - 9 pm"), (21, "8 pm - midnight")]);
+
+        assert_eq!(
+            restaurant.status_at(&at(21, 23, 45)),
+            OpenStatus::ClosingSoon {
+                closes_at: at(22, 0, 0)
+            }
+        );
+    }
+
+    #[test]
+    fn stays_open_at_end_of_schedule_when_open_all_day() {
+        let restaurant = scheduled_restaurant(&[(20, "7:30 am - 9 pm"), (21, "Open 24 hours")]);
+
+        assert_eq!(
+            restaurant.status_at(&at(21, 23, 45)),
+            OpenStatus::Open {
+                closes_at: at(22, 0, 0)
+            }
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use std::cmp::Ordering;
+
+/// The lowest score a [`SearchResult`] can have.
+const MIN_SCORE: f64 = 0.75;
+
+/// Codes for buildings on campus, and the words they stand for.
+const BUILDING_CODES: &[(&str, &str)] = &[
+    ("abb", "arthur bourns building"),
+    ("bsb", "burke science building"),
+    ("cnh", "chester new hall"),
+    ("dbac", "david braley athletic centre"),
+    ("dsb", "degroote school of business"),
+    ("etb", "engineering technology building"),
+    ("hsc", "health sciences centre"),
+    ("itb", "information technology building"),
+    ("jhe", "john hodgins engineering"),
+    ("kth", "kenneth taylor hall"),
+    ("lrw", "wilson hall"),
+    ("mdcl", "michael degroote centre for learning and discovery"),
+    ("mills", "mills library"),
+    ("musc", "mcmaster university student centre"),
+    ("pgcll", "peter george centre for living and learning"),
+    ("rjc", "ron joyce centre"),
+    ("thode", "thode library"),
+    ("tsh", "togo salmon hall"),
+];
+
+/// A match for a search query, with a score from `0` to `1` of how well it
+/// matches.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SearchResult<'a, T> {
+    /// The matching item.
+    pub item: &'a T,
+
+    /// How well the item matches the query, where `1` is a perfect match.
+    pub score: f64,
+}
+
+/// Score every item in `items` against `query`, keeping good enough matches,
+/// best first.
+///
+/// `fields` gets the text of each field of an item, along with how much a
+/// match in that field is worth.
+pub fn search<'a, T>(
+    items: &'a [T],
+    query: &str,
+    fields: impl Fn(&T) -> Vec<(&str, f64)>,
+) -> Vec<SearchResult<'a, T>> {
+    let query = words(query);
+
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let mut results = items
+        .iter()
+        .map(|item| {
+            let fields = fields(item)
+                .into_iter()
+                .map(|(text, weight)| (words(text), weight))
+                .collect::<Vec<_>>();
+
+            #[allow(clippy::cast_precision_loss)]
+            let score = query
+                .iter()
+                .map(|word| {
+                    fields
+                        .iter()
+                        .map(|(field, weight)| word_score(word, field) * weight)
+                        .fold(0.0, f64::max)
+                })
+                .sum::<f64>()
+                / query.len() as f64;
+
+            SearchResult { item, score }
+        })
+        .filter(|result| result.score >= MIN_SCORE)
+        .collect::<Vec<_>>();
+
+    results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal));
+
+    results
+}
+
+/// Split text into lowercase words, ignoring punctuation.
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Score how well a word of a query matches the words of a field.
+fn word_score(word: &str, field: &[String]) -> f64 {
+    let direct = field
+        .iter()
+        .map(|other| similarity(word, other))
+        .fold(0.0, f64::max);
+
+    let building = BUILDING_CODES
+        .iter()
+        .find(|(code, _)| *code == word)
+        .map_or(0.0, |(_, name)| {
+            let name = words(name);
+
+            #[allow(clippy::cast_precision_loss)]
+            let score =
+                name.iter().filter(|word| field.contains(word)).count() as f64 / name.len() as f64;
+
+            score
+        });
+
+    direct.max(building)
+}
+
+/// Score how similar two words are, allowing for typos and prefixes.
+fn similarity(word: &str, other: &str) -> f64 {
+    if word == other {
+        1.0
+    } else if word.len() >= 3 && other.starts_with(word) {
+        0.95
+    } else {
+        strsim::jaro_winkler(word, other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        test_util::{location, restaurant},
+        Restaurant,
+    };
+
+    fn restaurants() -> Vec<Restaurant> {
+        let centre = location(
+            "McMaster University Student Centre",
+            "mcmaster-university-student-centre",
+        );
+        let thode = location("Thode Library", "thode");
+
+        vec![
+            restaurant("Starbucks", &centre),
+            restaurant("Tim Hortons Express", &centre),
+            restaurant("Bridges Cafe", &location("Hedden Hall", "hedden")),
+            Restaurant {
+                location_details: Some("Lower level, beside the elevators".to_owned()),
+                ..restaurant("Tim Hortons", &thode)
+            },
+        ]
+    }
+
+    fn names<'a>(restaurants: &'a [Restaurant], query: &str) -> Vec<&'a str> {
+        Restaurant::search(restaurants, query)
+            .into_iter()
+            .map(|result| result.item.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn allows_typos() {
+        let restaurants = restaurants();
+
+        assert_eq!(names(&restaurants, "starbuks"), ["Starbucks"]);
+    }
+
+    #[test]
+    fn matches_building_codes() {
+        let restaurants = restaurants();
+
+        assert_eq!(
+            names(&restaurants, "MUSC"),
+            ["Starbucks", "Tim Hortons Express"]
+        );
+        assert_eq!(names(&restaurants, "THODE"), ["Tim Hortons"]);
+    }
+
+    #[test]
+    fn matches_location_details() {
+        let restaurants = restaurants();
+
+        assert_eq!(names(&restaurants, "elevators"), ["Tim Hortons"]);
+    }
+
+    #[test]
+    fn does_not_match_words_against_initials() {
+        let restaurants = restaurants();
+
+        assert_eq!(names(&restaurants, "the bridges"), ["Bridges Cafe"]);
+        assert!(!names(&restaurants, "the").contains(&"Tim Hortons Express"));
+    }
+}
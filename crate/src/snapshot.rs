@@ -0,0 +1,173 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    CoffeeBrand, FoodType, Location, LocationResolver, MacEatsClient, Restaurant, RestaurantId,
+    Result, DEFAULT_CLIENT,
+};
+
+/// Everything on MacEats at one point in time, which can be saved and loaded
+/// later without sending any requests.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// When the snapshot was fetched, which is when the location list was
+    /// fetched. If the snapshot was fetched from a mirror, this is when the
+    /// location list was saved.
+    pub fetched_at: DateTime<Utc>,
+
+    /// Every location.
+    pub locations: Vec<Location>,
+
+    /// Every restaurant, in the same order as the locations they are at.
+    pub restaurants: Vec<Restaurant>,
+
+    /// The coffee brands listed as served by each restaurant.
+    pub coffee_brand_membership: BTreeMap<RestaurantId, BTreeSet<CoffeeBrand>>,
+
+    /// The food types each restaurant is listed under.
+    pub food_type_membership: BTreeMap<RestaurantId, BTreeSet<FoodType>>,
+}
+
+impl Snapshot {
+    /// Fetch a [`Snapshot`] of MacEats.
+    ///
+    /// See [`MacEatsClient::snapshot`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if sending a request or parsing a
+    /// response fails.
+    pub async fn fetch() -> Result<Self> {
+        DEFAULT_CLIENT.snapshot().await
+    }
+
+    /// Serialize this [`Snapshot`] to JSON.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if serialization fails.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    /// Deserialize a [`Snapshot`] from JSON.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `json` is not a valid snapshot.
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Serialize this [`Snapshot`] to a compact binary format.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if serialization fails.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        Ok(bincode::serialize(self)?)
+    }
+
+    /// Deserialize a [`Snapshot`] from the binary format written by
+    /// [`Snapshot::to_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `bytes` is not a valid snapshot.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        Ok(bincode::deserialize(bytes)?)
+    }
+
+    /// Fetch a [`Snapshot`] of MacEats with `client`.
+    pub(crate) async fn fetch_with(client: &MacEatsClient) -> Result<Self> {
+        let (locations, fetched_at) = client.dated_location_list(client.locations_url()).await?;
+        let resolver = LocationResolver::new(locations.clone());
+
+        let mut restaurants = client.locations_restaurants(&locations).await?;
+
+        let coffee_brands = client.coffee_brands().await?;
+
+        let coffee_brand_membership = client
+            .coffee_brand_membership(&resolver, coffee_brands)
+            .await?;
+
+        let food_type_membership = client
+            .listing_membership(
+                &resolver,
+                FoodType::all().iter().filter_map(|food_type| {
+                    client
+                        .food_type_url(food_type)
+                        .map(|url| (url, food_type.clone()))
+                }),
+            )
+            .await?;
+
+        for restaurant in &mut restaurants {
+            if let Some(coffee_brands) = coffee_brand_membership.get(&restaurant.id()) {
+                restaurant
+                    .coffee_brands
+                    .extend(coffee_brands.iter().cloned());
+            }
+        }
+
+        Ok(Self {
+            fetched_at,
+            locations,
+            restaurants,
+            coffee_brand_membership,
+            food_type_membership,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::test_util::{location, restaurant, schedule};
+
+    fn snapshot() -> Snapshot {
+        let centre = location("Student Centre", "student-centre");
+        let late_night = Restaurant {
+            schedule: Some(schedule([
+                (20, "7:30 am - 9 pm, 10 pm - 2 am"),
+                (21, "Open 24 hours"),
+                (22, "Closed"),
+            ])),
+            tags: BTreeSet::from([FoodType::Coffee, FoodType::Unknown("Bubble Tea".to_owned())]),
+            coffee_brands: BTreeSet::from([CoffeeBrand::TimHortons]),
+            ..restaurant("Tim Hortons", &centre)
+        };
+        let id = late_night.id();
+
+        Snapshot {
+            fetched_at: Utc.with_ymd_and_hms(2022, 10, 20, 16, 0, 0).unwrap(),
+            locations: vec![centre.clone()],
+            restaurants: vec![late_night, restaurant("Starbucks", &centre)],
+            coffee_brand_membership: BTreeMap::from([(
+                id.clone(),
+                BTreeSet::from([CoffeeBrand::TimHortons]),
+            )]),
+            food_type_membership: BTreeMap::from([(id, BTreeSet::from([FoodType::Snacks]))]),
+        }
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let snapshot = snapshot();
+        let json = snapshot.to_json().unwrap();
+
+        assert_eq!(Snapshot::from_json(&json).unwrap(), snapshot);
+    }
+
+    #[test]
+    fn round_trips_through_bytes() {
+        let snapshot = snapshot();
+        let bytes = snapshot.to_bytes().unwrap();
+
+        assert_eq!(Snapshot::from_bytes(&bytes).unwrap(), snapshot);
+    }
+}
@@ -0,0 +1,160 @@
+use chrono::{DateTime, Duration};
+use chrono_tz::Tz;
+
+use crate::Restaurant;
+
+/// Whether a [`Restaurant`] is open at a given instant.
+///
+/// [`Restaurant`]: crate::Restaurant
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OpenStatus {
+    /// The restaurant is open.
+    Open {
+        /// When the restaurant closes, or when the schedule ends if the
+        /// restaurant is open all day on the last date it covers.
+        closes_at: DateTime<Tz>,
+    },
+
+    /// The restaurant is open, but closes soon.
+    ClosingSoon {
+        /// When the restaurant closes.
+        closes_at: DateTime<Tz>,
+    },
+
+    /// The restaurant is closed.
+    Closed {
+        /// When the restaurant next opens, or [`None`] if it does not open
+        /// again before the schedule ends.
+        opens_at: Option<DateTime<Tz>>,
+    },
+
+    /// The restaurant is closed, but opens soon.
+    OpeningSoon {
+        /// When the restaurant opens.
+        opens_at: DateTime<Tz>,
+    },
+
+    /// The restaurant has no schedule, or the instant is outside the dates it
+    /// covers.
+    Unknown,
+}
+
+impl OpenStatus {
+    /// How soon before a restaurant opens or closes that it is considered to be
+    /// opening or closing soon, by default.
+    pub const SOON: Duration = Duration::minutes(30);
+
+    /// Whether the restaurant is open.
+    #[must_use]
+    pub const fn is_open(&self) -> bool {
+        matches!(self, Self::Open { .. } | Self::ClosingSoon { .. })
+    }
+
+    /// Whether the restaurant is closed.
+    #[must_use]
+    pub const fn is_closed(&self) -> bool {
+        matches!(self, Self::Closed { .. } | Self::OpeningSoon { .. })
+    }
+
+    /// Whether the restaurant's status is unknown.
+    #[must_use]
+    pub const fn is_unknown(&self) -> bool {
+        matches!(self, Self::Unknown)
+    }
+}
+
+/// The result of comparing which [`Restaurant`]s are open according to their
+/// schedules with which are listed on MacEats' open now page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenNowReconciliation {
+    /// The instant the restaurants were compared at.
+    pub instant: DateTime<Tz>,
+
+    /// The restaurants open according to their schedules.
+    pub open: Vec<Restaurant>,
+
+    /// The restaurants whose schedules disagree with the open now page.
+    pub disagreements: Vec<OpenNowDisagreement>,
+}
+
+impl OpenNowReconciliation {
+    /// Compare `restaurants` at `instant` with the restaurants `listed` on the
+    /// open now page.
+    ///
+    /// Restaurants are matched by their name and location.
+    pub(crate) fn new(
+        restaurants: &[Restaurant],
+        listed: Vec<Restaurant>,
+        instant: DateTime<Tz>,
+    ) -> Self {
+        let is_listed = |restaurant: &Restaurant| {
+            listed
+                .iter()
+                .any(|r| r.name == restaurant.name && r.location == restaurant.location)
+        };
+
+        let mut open = Vec::new();
+        let mut disagreements = Vec::new();
+
+        for restaurant in restaurants {
+            let status = restaurant.status_at(&instant);
+            let listed = is_listed(restaurant);
+
+            if status.is_open() {
+                open.push(restaurant.clone());
+            }
+
+            if status.is_open() != listed {
+                disagreements.push(OpenNowDisagreement {
+                    restaurant: restaurant.clone(),
+                    status,
+                    listed,
+                });
+            }
+        }
+
+        disagreements.extend(
+            listed
+                .into_iter()
+                .filter(|r| {
+                    !restaurants.iter().any(|restaurant| {
+                        r.name == restaurant.name && r.location == restaurant.location
+                    })
+                })
+                .map(|restaurant| OpenNowDisagreement {
+                    restaurant,
+                    status: OpenStatus::Unknown,
+                    listed: true,
+                }),
+        );
+
+        Self {
+            instant,
+            open,
+            disagreements,
+        }
+    }
+
+    /// Whether the schedules agree with the open now page for every
+    /// restaurant.
+    #[must_use]
+    pub const fn is_consistent(&self) -> bool {
+        self.disagreements.is_empty()
+    }
+}
+
+/// A [`Restaurant`] whose schedule disagrees with MacEats' open now page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenNowDisagreement {
+    /// The restaurant.
+    ///
+    /// If the restaurant is only listed on the open now page, this is the
+    /// restaurant as parsed from that page.
+    pub restaurant: Restaurant,
+
+    /// The restaurant's status according to its schedule.
+    pub status: OpenStatus,
+
+    /// Whether the restaurant is listed on the open now page.
+    pub listed: bool,
+}
@@ -0,0 +1,199 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter},
+};
+
+use chrono::{Duration, NaiveDate, Timelike};
+use serde::{Deserialize, Serialize};
+
+use crate::{Open, Times};
+
+/// A compact summary of a [`Restaurant`]'s schedule, grouping consecutive days
+/// with the same hours.
+///
+/// It is displayed like `Mon–Fri 7:30 am – 9 pm · Sat 10 am – 4 pm · Sun
+/// Closed`.
+///
+/// [`Restaurant`]: crate::Restaurant
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct WeeklySummary {
+    /// The groups of consecutive days with the same hours, in order.
+    pub groups: Vec<DayGroup>,
+}
+
+impl WeeklySummary {
+    /// Summarize a schedule.
+    #[must_use]
+    pub fn new(schedule: &BTreeMap<NaiveDate, Times>) -> Self {
+        let mut groups: Vec<DayGroup> = Vec::new();
+
+        for (&date, times) in schedule {
+            match groups.last_mut() {
+                Some(group) if group.last + Duration::days(1) == date && group.times == *times => {
+                    group.last = date;
+                }
+                _ => groups.push(DayGroup {
+                    first: date,
+                    last: date,
+                    times: times.clone(),
+                }),
+            }
+        }
+
+        Self { groups }
+    }
+}
+
+impl From<&BTreeMap<NaiveDate, Times>> for WeeklySummary {
+    fn from(schedule: &BTreeMap<NaiveDate, Times>) -> Self {
+        Self::new(schedule)
+    }
+}
+
+impl Display for WeeklySummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.groups.iter().enumerate().try_for_each(|(i, group)| {
+            if i > 0 {
+                write!(f, " · ")?;
+            }
+
+            write!(f, "{group}")
+        })
+    }
+}
+
+/// A group of consecutive days with the same hours in a [`WeeklySummary`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct DayGroup {
+    /// The first day in the group.
+    pub first: NaiveDate,
+
+    /// The last day in the group.
+    pub last: NaiveDate,
+
+    /// The hours on every day in the group.
+    pub times: Times,
+}
+
+impl DayGroup {
+    /// Get every day in the group, in order.
+    pub fn days(&self) -> impl Iterator<Item = NaiveDate> + '_ {
+        self.first.iter_days().take_while(|&date| date <= self.last)
+    }
+}
+
+impl Display for DayGroup {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.first == self.last {
+            write!(f, "{}", self.first.format("%a"))?;
+        } else {
+            write!(f, "{}–{}", self.first.format("%a"), self.last.format("%a"))?;
+        }
+
+        match &self.times {
+            Times::Open(opens) => opens.iter().enumerate().try_for_each(|(i, open)| {
+                write!(f, "{}", if i > 0 { ", " } else { " " })?;
+
+                write_open(f, open)
+            }),
+            Times::Closed => write!(f, " Closed"),
+        }
+    }
+}
+
+/// Write an [`Open`] compactly, like `7:30 am – 9 pm`.
+fn write_open(f: &mut Formatter<'_>, open: &Open) -> fmt::Result {
+    if open.is_all_day() {
+        return write!(f, "Open 24 hours");
+    }
+
+    for (i, time) in [open.from, open.to].into_iter().enumerate() {
+        if i > 0 {
+            write!(f, " – ")?;
+        }
+
+        if time.minute() == 0 {
+            write!(f, "{}", time.format("%-l %P"))?;
+        } else {
+            write!(f, "{}", time.format("%-l:%M %P"))?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{date, schedule};
+
+    fn week() -> WeeklySummary {
+        // October 17, 2022 is a Monday.
+        WeeklySummary::new(&schedule([
+            (17, "7:30 am - 9 pm"),
+            (18, "7:30 am - 9 pm"),
+            (19, "7:30 am - 9 pm"),
+            (20, "7:30 am - 9 pm"),
+            (21, "7:30 am - 9 pm"),
+            (22, "10 am - 4 pm"),
+            (23, "Closed"),
+        ]))
+    }
+
+    #[test]
+    fn groups_consecutive_days_with_same_hours() {
+        let summary = week();
+
+        assert_eq!(
+            summary
+                .groups
+                .iter()
+                .map(|group| (group.first, group.last))
+                .collect::<Vec<_>>(),
+            [
+                (date(17), date(21)),
+                (date(22), date(22)),
+                (date(23), date(23))
+            ]
+        );
+        assert_eq!(summary.groups[0].days().count(), 5);
+    }
+
+    #[test]
+    fn splits_groups_at_gaps() {
+        let summary =
+            WeeklySummary::new(&schedule([(17, "Closed"), (18, "Closed"), (20, "Closed")]));
+
+        assert_eq!(summary.to_string(), "Mon–Tue Closed · Thu Closed");
+    }
+
+    #[test]
+    fn displays_compactly() {
+        assert_eq!(
+            week().to_string(),
+            "Mon–Fri 7:30 am – 9 pm · Sat 10 am – 4 pm · Sun Closed"
+        );
+
+        assert_eq!(
+            WeeklySummary::new(&schedule([
+                (20, "11 am - 2 pm, 10 pm - 2 am"),
+                (21, "Open 24 hours"),
+            ]))
+            .to_string(),
+            "Thu 11 am – 2 pm, 10 pm – 2 am · Fri Open 24 hours"
+        );
+
+        assert_eq!(WeeklySummary::default().to_string(), "");
+    }
+
+    #[test]
+    fn round_trips_through_serde() {
+        let summary = week();
+        let json = serde_json::to_string(&summary).unwrap();
+
+        assert_eq!(
+            serde_json::from_str::<WeeklySummary>(&json).unwrap(),
+            summary
+        );
+    }
+}
@@ -0,0 +1,61 @@
+//! Fixtures shared between unit tests.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::{DateTime, NaiveDate, NaiveTime};
+use chrono_tz::Tz;
+
+use crate::{schedule, Location, Restaurant, Times};
+
+/// Get the date `year`-`month`-`day`.
+pub fn ymd(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+/// Get the date `day` October 2022. October 20, 2022 is a Thursday.
+pub fn date(day: u32) -> NaiveDate {
+    ymd(2022, 10, day)
+}
+
+/// Get the instant `hour`:`minute` on `day` October 2022 on campus.
+pub fn at(day: u32, hour: u32, minute: u32) -> DateTime<Tz> {
+    schedule::instant(date(day), NaiveTime::from_hms_opt(hour, minute, 0).unwrap())
+}
+
+/// Create a [`Location`] named `name` with the slug `slug`.
+pub fn location(name: &str, slug: &str) -> Location {
+    Location {
+        name: name.to_owned(),
+        slug: slug.to_owned(),
+    }
+}
+
+/// Create a [`Restaurant`] named `name` at `location`, with no schedule, tags
+/// or coffee brands.
+pub fn restaurant(name: &str, location: &Location) -> Restaurant {
+    Restaurant {
+        name: name.to_owned(),
+        location: location.clone(),
+        location_details: None,
+        location_phone: None,
+        schedule: None,
+        tags: BTreeSet::new(),
+        coffee_brands: BTreeSet::new(),
+    }
+}
+
+/// Create a [`Restaurant`] with the schedule `days` (see [`schedule`]).
+pub fn scheduled_restaurant(days: &[(u32, &str)]) -> Restaurant {
+    Restaurant {
+        schedule: Some(schedule(days.iter().copied())),
+        ..restaurant("Tim Hortons", &location("Student Centre", "student-centre"))
+    }
+}
+
+/// Create a schedule from pairs of days in October 2022 and times, e.g.
+/// `(20, "7:30 am - 9 pm")`.
+pub fn schedule<'a>(days: impl IntoIterator<Item = (u32, &'a str)>) -> BTreeMap<NaiveDate, Times> {
+    days.into_iter()
+        .map(|(day, times)| (date(day), times.parse().unwrap()))
+        .collect()
+}
@@ -3,12 +3,13 @@ use std::{
     str::FromStr,
 };
 
-use chrono::NaiveTime;
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Timelike};
+use chrono_tz::Tz;
 use scraper::ElementRef;
 use selectors::attr::CaseSensitivity;
 use serde::{Deserialize, Serialize};
 
-use crate::{regex, Error, Result};
+use crate::{regex, schedule, Error, Result};
 
 /// The times a [`Restaurant`] is open on a given day.
 ///
@@ -23,6 +24,145 @@ pub enum Times {
     Closed,
 }
 
+impl Times {
+    /// Get these times with the ranges sorted, and overlapping or adjacent
+    /// ranges merged.
+    ///
+    /// A merged range that would last longer than a day is cut short to last
+    /// exactly one day, and times with no ranges are [`Times::Closed`].
+    #[must_use]
+    pub fn normalized(&self) -> Self {
+        Self::from_spans(self.spans())
+    }
+
+    /// Get the times either `self` or `other` are open.
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        let mut spans = self.spans();
+        spans.extend(other.spans());
+
+        Self::from_spans(merge(spans))
+    }
+
+    /// Get the times both `self` and `other` are open.
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Self {
+        let other = other.spans();
+
+        Self::from_spans(
+            self.spans()
+                .into_iter()
+                .flat_map(|(from, to)| {
+                    other.iter().map(move |&(other_from, other_to)| {
+                        (from.max(other_from), to.min(other_to))
+                    })
+                })
+                .filter(|(from, to)| from < to)
+                .collect(),
+        )
+    }
+
+    /// Get the times `self` is open but `other` is not.
+    ///
+    /// If only the part of an overnight range after midnight is left, it is
+    /// dropped, as [`Times`] can only hold ranges that open on its own day.
+    #[must_use]
+    pub fn difference(&self, other: &Self) -> Self {
+        let other = other.spans();
+
+        Self::from_spans(
+            self.spans()
+                .into_iter()
+                .flat_map(|span| {
+                    other
+                        .iter()
+                        .fold(vec![span], |spans, &(other_from, other_to)| {
+                            spans
+                                .into_iter()
+                                .flat_map(|(from, to)| {
+                                    [(from, to.min(other_from)), (from.max(other_to), to)]
+                                })
+                                .filter(|(from, to)| from < to)
+                                .collect()
+                        })
+                })
+                .collect(),
+        )
+    }
+
+    /// Get the total time these times are open, counting overlapping ranges
+    /// once.
+    ///
+    /// As in [`Times::normalized`], no merged range counts for more than a
+    /// day.
+    #[must_use]
+    pub fn total_duration(&self) -> Duration {
+        Duration::seconds(
+            self.spans()
+                .into_iter()
+                .map(|(from, to)| i64::from(to.min(from + DAY) - from))
+                .sum(),
+        )
+    }
+
+    /// Get the ranges as seconds after the start of the day, sorted and
+    /// merged.
+    fn spans(&self) -> Vec<(u32, u32)> {
+        match self {
+            Self::Open(opens) => merge(opens.iter().map(Open::span).collect()),
+            Self::Closed => Vec::new(),
+        }
+    }
+
+    /// Create [`Times`] from sorted and merged ranges of seconds after the
+    /// start of the day.
+    ///
+    /// Ranges that start on the next day are dropped.
+    fn from_spans(spans: Vec<(u32, u32)>) -> Self {
+        let spans = spans
+            .into_iter()
+            .filter(|&(from, _)| from < DAY)
+            .collect::<Vec<_>>();
+
+        if spans.is_empty() {
+            return Self::Closed;
+        }
+
+        Self::Open(
+            spans
+                .into_iter()
+                .map(|(from, to)| {
+                    let time = |secs| {
+                        NaiveTime::from_num_seconds_from_midnight_opt(secs % DAY, 0)
+                            .expect("seconds should be less than a day")
+                    };
+
+                    Open::new(time(from), time(to.min(from + DAY)))
+                })
+                .collect(),
+        )
+    }
+}
+
+/// The number of seconds in a day.
+const DAY: u32 = 24 * 60 * 60;
+
+/// Sort ranges of seconds, and merge overlapping or adjacent ranges.
+fn merge(mut spans: Vec<(u32, u32)>) -> Vec<(u32, u32)> {
+    spans.sort_unstable();
+
+    spans
+        .into_iter()
+        .fold(Vec::new(), |mut merged, (from, to)| {
+            match merged.last_mut() {
+                Some((_, last_to)) if from <= *last_to => *last_to = to.max(*last_to),
+                _ => merged.push((from, to)),
+            }
+
+            merged
+        })
+}
+
 impl Display for Times {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
@@ -42,11 +182,16 @@ impl FromStr for Times {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self> {
-        if s == "Closed" {
+        let s = s.trim();
+
+        if s.eq_ignore_ascii_case("closed") {
             Ok(Self::Closed)
         } else {
             Ok(Self::Open(
-                s.split(", ").map(str::parse).collect::<Result<_>>()?,
+                regex!(r"(?i)\s*(?:,|&|\band\b)\s*")
+                    .split(s)
+                    .map(str::parse)
+                    .collect::<Result<_>>()?,
             ))
         }
     }
@@ -64,33 +209,218 @@ impl TryFrom<ElementRef<'_>> for Times {
         let text = element
             .text()
             .next()
-            .ok_or_else(|| Error::TextNotFound("time"))?
+            .ok_or_else(|| Error::text_not_found("time", "td.time").with_element(element))?
             .trim();
 
         text.parse()
+            .map_err(|e: Error| e.with_selector("td.time").with_element(element))
     }
 }
 
 /// The times a [`Restaurant`] is open on a given day.
 ///
+/// A range that closes at or before the time it opens (e.g. `10 pm - 2 am`)
+/// ends on the next day, which is recorded explicitly in
+/// [`Open::ends_next_day`].
+///
+/// Times are given in the campus timezone ([`TIMEZONE`]). Use
+/// [`Open::start_on`] and [`Open::end_on`] to get the instants a range starts
+/// and ends on a given date.
+///
 /// [`Restaurant`]: crate::Restaurant
+/// [`TIMEZONE`]: crate::TIMEZONE
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(from = "OpenRepr", into = "OpenRepr")]
 pub struct Open {
-    /// The time the restaurant opens.
+    /// The time the restaurant opens, in the campus timezone.
     pub from: NaiveTime,
 
-    /// The time the restaurant closes.
+    /// The time the restaurant closes, in the campus timezone.
     pub to: NaiveTime,
+
+    /// Whether the restaurant closes on the day after it opens.
+    pub ends_next_day: bool,
+}
+
+/// The serialized form of an [`Open`], which may be missing
+/// [`Open::ends_next_day`] if it was saved by an older version of this crate.
+///
+/// [`Open`] is always serialized through this form, so that formats that are
+/// not self-describing read back the same shape they wrote.
+#[derive(Serialize, Deserialize)]
+struct OpenRepr {
+    from: NaiveTime,
+    to: NaiveTime,
+    ends_next_day: Option<bool>,
+}
+
+impl From<OpenRepr> for Open {
+    fn from(repr: OpenRepr) -> Self {
+        Self {
+            from: repr.from,
+            to: repr.to,
+            ends_next_day: repr.ends_next_day.unwrap_or(repr.to <= repr.from),
+        }
+    }
+}
+
+impl From<Open> for OpenRepr {
+    fn from(open: Open) -> Self {
+        Self {
+            from: open.from,
+            to: open.to,
+            ends_next_day: Some(open.ends_next_day),
+        }
+    }
+}
+
+impl Open {
+    /// Create a new [`Open`], which ends on the next day if `to` is not after
+    /// `from`.
+    #[must_use]
+    pub fn new(from: NaiveTime, to: NaiveTime) -> Self {
+        Self {
+            from,
+            to,
+            ends_next_day: to <= from,
+        }
+    }
+
+    /// Create a new [`Open`] that lasts all day.
+    #[must_use]
+    pub const fn all_day() -> Self {
+        Self {
+            from: NaiveTime::MIN,
+            to: NaiveTime::MIN,
+            ends_next_day: true,
+        }
+    }
+
+    /// Whether this [`Open`] lasts all day.
+    #[must_use]
+    pub fn is_all_day(&self) -> bool {
+        *self == Self::all_day()
+    }
+
+    /// Get how long this [`Open`] lasts.
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        let duration = self.to - self.from;
+
+        if self.ends_next_day {
+            duration + Duration::days(1)
+        } else {
+            duration
+        }
+    }
+
+    /// Get the instant this range starts if it opens on `date`, in the campus
+    /// timezone ([`TIMEZONE`]).
+    ///
+    /// A time skipped by a DST transition is taken to be an hour later, and a
+    /// time repeated by one is taken to be its first occurrence.
+    ///
+    /// [`TIMEZONE`]: crate::TIMEZONE
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::NaiveDate;
+    /// use maceats::Open;
+    ///
+    /// let open: Open = "7:30 am - 9 pm".parse()?;
+    /// let date = NaiveDate::from_ymd_opt(2022, 10, 20).unwrap();
+    /// assert_eq!(open.start_on(date).to_rfc3339(), "2022-10-20T07:30:00-04:00");
+    ///
+    /// // Clocks went forward from 2 am to 3 am on March 13, 2022.
+    /// let open: Open = "2:30 am - 9 pm".parse()?;
+    /// let date = NaiveDate::from_ymd_opt(2022, 3, 13).unwrap();
+    /// assert_eq!(open.start_on(date).to_rfc3339(), "2022-03-13T03:30:00-04:00");
+    /// # Ok::<(), maceats::Error>(())
+    /// ```
+    #[must_use]
+    pub fn start_on(&self, date: NaiveDate) -> DateTime<Tz> {
+        schedule::instant(date, self.from)
+    }
+
+    /// Get the instant this range ends if it opens on `date`, in the campus
+    /// timezone ([`TIMEZONE`]).
+    ///
+    /// This is on the day after `date` if the range ends the next day. DST
+    /// transitions are handled as in [`Open::start_on`].
+    ///
+    /// [`TIMEZONE`]: crate::TIMEZONE
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::NaiveDate;
+    /// use maceats::Open;
+    ///
+    /// let open: Open = "10 pm - 2 am".parse()?;
+    /// let date = NaiveDate::from_ymd_opt(2022, 10, 20).unwrap();
+    /// assert_eq!(open.end_on(date).to_rfc3339(), "2022-10-21T02:00:00-04:00");
+    ///
+    /// // Clocks went back from 2 am to 1 am on November 6, 2022.
+    /// let open: Open = "10 pm - 1:30 am".parse()?;
+    /// let date = NaiveDate::from_ymd_opt(2022, 11, 5).unwrap();
+    /// assert_eq!(open.end_on(date).to_rfc3339(), "2022-11-06T01:30:00-04:00");
+    /// # Ok::<(), maceats::Error>(())
+    /// ```
+    #[must_use]
+    pub fn end_on(&self, date: NaiveDate) -> DateTime<Tz> {
+        let date = if self.ends_next_day {
+            date + Duration::days(1)
+        } else {
+            date
+        };
+
+        schedule::instant(date, self.to)
+    }
+
+    /// Get how long this range actually lasts if it opens on `date`, which
+    /// differs from [`Open::duration`] if it spans a DST transition.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::{Duration, NaiveDate};
+    /// use maceats::Open;
+    ///
+    /// let open = Open::all_day();
+    ///
+    /// let date = NaiveDate::from_ymd_opt(2022, 3, 13).unwrap();
+    /// assert_eq!(open.duration_on(date), Duration::hours(23));
+    ///
+    /// let date = NaiveDate::from_ymd_opt(2022, 11, 6).unwrap();
+    /// assert_eq!(open.duration_on(date), Duration::hours(25));
+    /// ```
+    #[must_use]
+    pub fn duration_on(&self, date: NaiveDate) -> Duration {
+        self.end_on(date) - self.start_on(date)
+    }
+
+    /// Get this range as seconds after the start of the day it opens.
+    fn span(&self) -> (u32, u32) {
+        let from = self.from.num_seconds_from_midnight();
+        let to = self.to.num_seconds_from_midnight();
+
+        (from, if self.ends_next_day { to + DAY } else { to })
+    }
 }
 
 impl Display for Open {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{} - {}",
-            self.from.format("%l:%M %P").to_string().trim(),
-            self.to.format("%l:%M %P").to_string().trim(),
-        )
+        if self.is_all_day() {
+            write!(f, "Open 24 hours")
+        } else {
+            write!(
+                f,
+                "{} - {}",
+                self.from.format("%l:%M %P").to_string().trim(),
+                self.to.format("%l:%M %P").to_string().trim(),
+            )
+        }
     }
 }
 
@@ -98,16 +428,387 @@ impl FromStr for Open {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self> {
-        let (from_s, to_s) = s.split_once(" - ").ok_or(Error::SplitTime)?;
+        let s = s.trim();
+
+        if regex!(r"(?i)^(?:open\s+)?24\s*(?:hours|hrs)$").is_match(s) {
+            return Ok(Self::all_day());
+        }
+
+        let mut split = regex!(r"(?i)\s*(?:-|–|—|\bto\b)\s*").splitn(s, 2);
+
+        let (Some(from_s), Some(to_s)) = (split.next(), split.next()) else {
+            return Err(Error::split_time());
+        };
+
+        let from = Clock::parse(from_s)?;
+        let to = Clock::parse(to_s)?;
 
-        let re = regex!(r"^(?P<hour>\d{1,2}) (?P<am_pm>am|pm)$");
+        let (from, to) = match (from.pm(), to.pm()) {
+            (Some(from_pm), Some(to_pm)) => (from.at(from_pm), to.at(to_pm)),
+            (None, Some(pm)) => {
+                let to = to.at(pm);
+                let same = from.at(pm);
 
-        let from_s = re.replace_all(from_s, "$hour:00 $am_pm");
-        let to_s = re.replace_all(to_s, "$hour:00 $am_pm");
+                (if same > to { from.at(!pm) } else { same }, to)
+            }
+            (Some(pm), None) => {
+                let from = from.at(pm);
+                let same = to.at(pm);
+
+                (from, if same <= from { to.at(!pm) } else { same })
+            }
+            (None, None) => (from.at(false), to.at(false)),
+        };
+
+        Ok(Self::new(from, to))
+    }
+}
+
+/// A time of day as written on MacEats, which may be missing its am/pm.
+#[derive(Debug, Clone, Copy)]
+enum Clock {
+    /// A time that is unambiguous on its own, such as `noon` or `21:00`.
+    Exact(NaiveTime),
+
+    /// A time on a 12-hour clock, such as `7:30 am` or `9`.
+    Twelve {
+        hour: u32,
+        minute: u32,
+        pm: Option<bool>,
+    },
+}
+
+impl Clock {
+    fn parse(s: &str) -> Result<Self> {
+        let s = s.trim();
+
+        if s.eq_ignore_ascii_case("noon") || s.eq_ignore_ascii_case("midday") {
+            return Ok(Self::Exact(
+                NaiveTime::from_hms_opt(12, 0, 0).expect("noon is valid"),
+            ));
+        }
+
+        if s.eq_ignore_ascii_case("midnight") {
+            return Ok(Self::Exact(NaiveTime::MIN));
+        }
+
+        let captures = regex!(
+            r"(?i)^(?P<hour>\d{1,2})(?:[:.](?P<minute>\d{2}))?\s*(?:(?P<meridiem>[ap])\.?\s*m\.?)?$"
+        )
+        .captures(s)
+        .ok_or_else(|| Error::parse_time(s))?;
+
+        let hour = captures["hour"].parse().map_err(|_| Error::parse_time(s))?;
+        let minute = captures
+            .name("minute")
+            .map_or(Ok(0), |minute| minute.as_str().parse())
+            .map_err(|_| Error::parse_time(s))?;
+        let pm = captures
+            .name("meridiem")
+            .map(|meridiem| meridiem.as_str().eq_ignore_ascii_case("p"));
+
+        match (hour, pm) {
+            (1..=12, _) if minute < 60 => Ok(Self::Twelve { hour, minute, pm }),
+            (0 | 13..=23, None) => NaiveTime::from_hms_opt(hour, minute, 0)
+                .map(Self::Exact)
+                .ok_or_else(|| Error::parse_time(s)),
+            _ => Err(Error::parse_time(s)),
+        }
+    }
+
+    /// Whether this time is known to be am (`Some(false)`) or pm
+    /// (`Some(true)`).
+    const fn pm(self) -> Option<bool> {
+        match self {
+            Self::Exact(_) => None,
+            Self::Twelve { pm, .. } => pm,
+        }
+    }
+
+    /// Get this time of day, assuming it is pm if `pm` is `true` and the time
+    /// is ambiguous.
+    const fn at(self, pm: bool) -> NaiveTime {
+        match self {
+            Self::Exact(time) => time,
+            Self::Twelve { hour, minute, .. } => {
+                NaiveTime::from_hms_opt(hour % 12 + if pm { 12 } else { 0 }, minute, 0)
+                    .expect("12-hour time should be valid")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn open(s: &str) -> Open {
+        s.parse()
+            .unwrap_or_else(|e| panic!("{s:?} should parse: {e}"))
+    }
+
+    #[test]
+    fn parses_ranges() {
+        assert_eq!(open("7:30 am - 9 pm"), Open::new(time(7, 30), time(21, 0)));
+        assert_eq!(open("7:30am-9:00pm"), Open::new(time(7, 30), time(21, 0)));
+        assert_eq!(
+            open("7:30 a.m. - 9 p.m."),
+            Open::new(time(7, 30), time(21, 0))
+        );
+        assert_eq!(
+            open("12 pm - 12:30 pm"),
+            Open::new(time(12, 0), time(12, 30))
+        );
+        assert_eq!(open("21:00 - 23:30"), Open::new(time(21, 0), time(23, 30)));
+    }
+
+    #[test]
+    fn parses_range_separators() {
+        let expected = Open::new(time(11, 0), time(14, 0));
+
+        assert_eq!(open("11 am – 2 pm"), expected);
+        assert_eq!(open("11 am — 2 pm"), expected);
+        assert_eq!(open("11 am to 2 pm"), expected);
+        assert_eq!(open("11 AM TO 2 PM"), expected);
+    }
+
+    #[test]
+    fn parses_noon_and_midnight() {
+        assert_eq!(open("Noon - 8 pm"), Open::new(time(12, 0), time(20, 0)));
+        assert_eq!(open("11 am - midday"), Open::new(time(11, 0), time(12, 0)));
+
+        let late = open("8 pm - Midnight");
+        assert_eq!(late, Open::new(time(20, 0), time(0, 0)));
+        assert!(late.ends_next_day);
+    }
+
+    #[test]
+    fn infers_missing_meridiem() {
+        assert_eq!(open("11 - 2 pm"), Open::new(time(11, 0), time(14, 0)));
+        assert_eq!(open("7 - 9 pm"), Open::new(time(19, 0), time(21, 0)));
+        assert_eq!(open("9 am - 5"), Open::new(time(9, 0), time(17, 0)));
+        assert_eq!(open("7:30 - 11"), Open::new(time(7, 30), time(11, 0)));
+
+        let late = open("10 pm - 2");
+        assert_eq!(late, Open::new(time(22, 0), time(2, 0)));
+        assert!(late.ends_next_day);
+    }
+
+    #[test]
+    fn parses_overnight_ranges() {
+        let late = open("10 pm - 2 am");
+
+        assert!(late.ends_next_day);
+        assert_eq!(late.duration(), Duration::hours(4));
+        assert!(!open("7:30 am - 9 pm").ends_next_day);
+    }
+
+    #[test]
+    fn parses_all_day() {
+        for s in ["Open 24 hours", "open 24 hours", "24 hours", "24 hrs"] {
+            let all_day = open(s);
+
+            assert!(all_day.is_all_day(), "{s:?}");
+            assert_eq!(all_day.duration(), Duration::days(1));
+            assert_eq!(all_day.to_string(), "Open 24 hours");
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_ranges() {
+        assert!(matches!(
+            "9 am".parse::<Open>(),
+            Err(Error::SplitTime { .. })
+        ));
+        assert!(matches!(
+            "25 - 3 pm".parse::<Open>(),
+            Err(Error::ParseTime { .. })
+        ));
+        assert!(matches!(
+            "13 pm - 3 pm".parse::<Open>(),
+            Err(Error::ParseTime { .. })
+        ));
+        assert!(matches!(
+            "9:75 am - 3 pm".parse::<Open>(),
+            Err(Error::ParseTime { .. })
+        ));
+        assert!(matches!(
+            "soon - 3 pm".parse::<Open>(),
+            Err(Error::ParseTime { .. })
+        ));
+    }
+
+    #[test]
+    fn parses_times() {
+        assert_eq!("Closed".parse::<Times>().unwrap(), Times::Closed);
+        assert_eq!(
+            "11 am - 2 pm & 5 pm - 8 pm".parse::<Times>().unwrap(),
+            Times::Open(vec![
+                Open::new(time(11, 0), time(14, 0)),
+                Open::new(time(17, 0), time(20, 0)),
+            ])
+        );
+        assert_eq!(
+            "11 am - 2 pm and 5 - 8 pm".parse::<Times>().unwrap(),
+            "11 am - 2 pm, 5 pm - 8 pm".parse::<Times>().unwrap(),
+        );
+    }
+
+    fn times(s: &str) -> Times {
+        s.parse()
+            .unwrap_or_else(|e| panic!("{s:?} should parse: {e}"))
+    }
+
+    #[test]
+    fn normalizes_overlapping_ranges() {
+        assert_eq!(
+            times("11 am - 3 pm, 9 am - 1 pm").normalized(),
+            times("9 am - 3 pm")
+        );
+        assert_eq!(
+            times("9 am - 5 pm, 11 am - 1 pm").normalized(),
+            times("9 am - 5 pm")
+        );
+    }
+
+    #[test]
+    fn normalizes_adjacent_ranges() {
+        assert_eq!(
+            times("11 am - 1 pm, 9 am - 11 am").normalized(),
+            times("9 am - 1 pm")
+        );
+        assert_eq!(
+            times("5 pm - 8 pm, 9 am - 1 pm").normalized(),
+            times("9 am - 1 pm, 5 pm - 8 pm")
+        );
+    }
+
+    #[test]
+    fn normalizes_empty_times_to_closed() {
+        assert_eq!(Times::Open(Vec::new()).normalized(), Times::Closed);
+        assert_eq!(Times::Closed.normalized(), Times::Closed);
+        assert_eq!(Times::Closed.total_duration(), Duration::zero());
+    }
+
+    #[test]
+    fn unions_ranges() {
+        assert_eq!(
+            times("9 am - 1 pm").union(&times("noon - 3 pm")),
+            times("9 am - 3 pm")
+        );
+        assert_eq!(
+            times("9 am - 1 pm").union(&Times::Closed),
+            times("9 am - 1 pm")
+        );
+        assert_eq!(
+            times("10 pm - 1 am").union(&times("11 pm - 2 am")),
+            times("10 pm - 2 am")
+        );
+    }
+
+    #[test]
+    fn intersects_ranges() {
+        assert_eq!(
+            times("9 am - 1 pm").intersection(&times("noon - 3 pm")),
+            times("noon - 1 pm")
+        );
+        assert_eq!(
+            times("8 pm - 11 pm").intersection(&times("10 pm - 2 am")),
+            times("10 pm - 11 pm")
+        );
+        assert_eq!(
+            times("10 pm - 2 am").intersection(&times("Open 24 hours")),
+            times("10 pm - midnight")
+        );
+        assert_eq!(
+            times("9 am - 11 am").intersection(&times("11 am - 1 pm")),
+            Times::Closed
+        );
+    }
+
+    #[test]
+    fn differences_ranges() {
+        assert_eq!(
+            times("9 am - 5 pm").difference(&times("noon - 1 pm")),
+            times("9 am - noon, 1 pm - 5 pm")
+        );
+        assert_eq!(
+            times("9 am - 5 pm").difference(&times("9 am - 5 pm")),
+            Times::Closed
+        );
+        assert_eq!(
+            times("10 pm - 2 am").difference(&times("11 pm - 1 am")),
+            times("10 pm - 11 pm")
+        );
+        assert_eq!(
+            times("10 pm - 2 am").difference(&times("9 pm - 11 pm")),
+            times("11 pm - 2 am")
+        );
+    }
+
+    #[test]
+    fn clamps_merged_ranges_to_a_day() {
+        let long = times("6 am - 5 am").union(&times("4 am - 10 am"));
+
+        assert_eq!(long, times("4 am - 4 am"));
+        assert_eq!(long.total_duration(), Duration::days(1));
+
+        assert_eq!(
+            times("Open 24 hours").union(&times("10 pm - 2 am")),
+            times("Open 24 hours")
+        );
+    }
+
+    #[test]
+    fn totals_durations() {
+        assert_eq!(
+            times("9 am - 1 pm, 11 am - 3 pm").total_duration(),
+            Duration::hours(6)
+        );
+        assert_eq!(times("10 pm - 2 am").total_duration(), Duration::hours(4));
+        assert_eq!(
+            times("6 am - 5 am, 4 am - 10 am").total_duration(),
+            Duration::days(1)
+        );
+    }
+
+    #[test]
+    fn deserializes_ranges_without_ends_next_day() {
+        let late: Open = serde_json::from_str(r#"{"from":"22:00:00","to":"02:00:00"}"#).unwrap();
+        assert_eq!(late, Open::new(time(22, 0), time(2, 0)));
+        assert!(late.ends_next_day);
+
+        let day: Open = serde_json::from_str(r#"{"from":"07:30:00","to":"21:00:00"}"#).unwrap();
+        assert!(!day.ends_next_day);
+    }
+
+    #[test]
+    fn round_trips_through_serde() {
+        for open in [
+            open("7:30 am - 9 pm"),
+            open("10 pm - 2 am"),
+            Open::all_day(),
+        ] {
+            let json = serde_json::to_string(&open).unwrap();
+
+            assert_eq!(serde_json::from_str::<Open>(&json).unwrap(), open);
+        }
+    }
 
-        let from = NaiveTime::parse_from_str(&from_s, "%l:%M %P")?;
-        let to = NaiveTime::parse_from_str(&to_s, "%l:%M %P")?;
+    #[test]
+    fn round_trips_through_bincode() {
+        let opens = vec![
+            open("7:30 am - 9 pm"),
+            open("10 pm - 2 am"),
+            open("10 pm - 2 am"),
+            Open::all_day(),
+        ];
+        let bytes = bincode::serialize(&opens).unwrap();
 
-        Ok(Self { from, to })
+        assert_eq!(bincode::deserialize::<Vec<Open>>(&bytes).unwrap(), opens);
     }
 }